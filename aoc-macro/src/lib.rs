@@ -29,7 +29,7 @@ pub fn match_and_run_day_both(_input: TokenStream) -> TokenStream {
     let res = quote! {
         match day {
             #(#r => {
-                Day::<#r>::run_timed(input.trim_end())
+                Day::<#r>::run_timed(input, map)
             })*
             _ => panic!("Days out of Bounds! No presents for you!"),
         }
@@ -43,7 +43,7 @@ pub fn match_and_run_day_one(_input: TokenStream) -> TokenStream {
     let res = quote! {
         match day {
             #(#r => {
-                Day::<#r>::run_one_timed(input.trim_end())
+                Day::<#r>::run_one_timed(input, map)
             })*
             _ => panic!("Days out of Bounds! No presents for you!"),
         }
@@ -57,7 +57,21 @@ pub fn match_and_run_day_two(_input: TokenStream) -> TokenStream {
     let res = quote! {
         match day {
             #(#r => {
-                Day::<#r>::run_two_timed(input.trim_end())
+                Day::<#r>::run_two_timed(input, map)
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_and_run_day_extra(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::run_extra_timed(input, name, map)
             })*
             _ => panic!("Days out of Bounds! No presents for you!"),
         }
@@ -106,3 +120,101 @@ pub fn match_and_test_day_two(_input: TokenStream) -> TokenStream {
     };
     res.into()
 }
+
+#[proc_macro]
+pub fn match_and_test_day_extra(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::test_extra(name)
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_day_extra_part_names(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::extra_part_names()
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_and_render_test_day_map(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::render_test_map()
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_and_crosscheck_day(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::crosscheck(input)
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_and_crosscheck_test_day(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::crosscheck_test()
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_day_tool_commands(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::tool_commands()
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_and_run_day_tool(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::run_tool(input, matches)
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
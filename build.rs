@@ -0,0 +1,17 @@
+//! Generates `include/aoc24.h` from the C ABI in `src/capi.rs` when building with the `capi` feature.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "capi")]
+    {
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+
+        let root = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_root_or_default(&root);
+        cbindgen::generate_with_config(&root, config)
+            .expect("Couldn't generate the C header")
+            .write_to_file(format!("{}/include/aoc24.h", root));
+    }
+}
@@ -0,0 +1,74 @@
+/* Generated by cbindgen from src/capi.rs when building with the capi feature, don't edit.
+ *
+ * Build the library with `cargo build --release --features capi`
+ * and link against libaoc24 from target/release.
+ */
+
+#ifndef AOC24_H
+#define AOC24_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/*
+ What an [`Aoc24Result`] holds, mirrors [`SolveError`].
+ */
+typedef enum Aoc24Kind {
+  AOC24_KIND_OK = 0,
+  AOC24_KIND_NO_SOLUTION = 1,
+  AOC24_KIND_INVALID_INPUT = 2,
+  AOC24_KIND_UNIMPLEMENTED = 3,
+  AOC24_KIND_UNKNOWN_VARIANT = 4,
+  /*
+   The day panicked.
+   */
+  AOC24_KIND_PANICKED = 5,
+  /*
+   The day, part or input passed to [`aoc24_solve`] isn't valid.
+   */
+  AOC24_KIND_INVALID_ARGUMENTS = 6,
+} Aoc24Kind;
+
+typedef struct Aoc24Result {
+  enum Aoc24Kind kind;
+  /*
+   The answer as it would be submitted if `kind` is `Ok`, a description of the error otherwise.
+   */
+  char *answer;
+  uint64_t parse_ns;
+  uint64_t precompute_ns;
+  uint64_t part_ns;
+} Aoc24Result;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Solves a part of a day, 1 or 2, on `len` bytes of UTF-8 input.
+
+ Never returns null. The result is owned by the caller and has to be freed with [`aoc24_free_result`].
+
+ # Safety
+
+ `input` must point to `len` readable bytes, it may only be null if `len` is 0.
+ */
+struct Aoc24Result *aoc24_solve(uint8_t day,
+                                uint8_t part,
+                                const uint8_t *input,
+                                size_t len);
+
+/*
+ Frees a result returned by [`aoc24_solve`], including its answer. Does nothing for null.
+
+ # Safety
+
+ `result` must come from [`aoc24_solve`] and must not be used or freed again afterwards.
+ */
+void aoc24_free_result(struct Aoc24Result *result);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AOC24_H */
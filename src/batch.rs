@@ -0,0 +1,205 @@
+use crate::days::{Answer, SolveError};
+use crate::history::RunRecord;
+use crate::{Part, dynamic_range_time_format, input, solve_day};
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The extension of the files holding the expected answers of the input with the same stem.
+///
+/// The first line is the answer to part 1, the second the answer to part 2.
+/// Missing or empty lines, or `?`, mean the answer is unknown.
+pub const EXPECTED_EXTENSION: &str = "expected";
+
+/// An input of a batch run.
+#[derive(Debug, Clone)]
+pub struct BatchInput {
+    pub path: PathBuf,
+    pub input: String,
+    pub expected: [Option<String>; 2],
+}
+
+/// Parses the contents of an [`EXPECTED_EXTENSION`] file.
+pub fn parse_expected(expected: &str) -> [Option<String>; 2] {
+    let mut lines = expected.lines().map(|v| {
+        let v = v.trim();
+        (!v.is_empty() && v != "?").then(|| v.to_string())
+    });
+    [lines.next().flatten(), lines.next().flatten()]
+}
+
+/// Loads every input in a directory, sorted by name, together with their expected answers.
+/// Hidden files and the expected answer files themselves are skipped.
+pub fn load(dir: &Path) -> std::io::Result<Vec<BatchInput>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|v| v.path()))
+        .collect::<std::io::Result<_>>()?;
+    paths.retain(|path| {
+        path.is_file()
+            && path.extension().is_none_or(|v| v != EXPECTED_EXTENSION)
+            && !path
+                .file_name()
+                .is_some_and(|v| v.to_string_lossy().starts_with('.'))
+    });
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let input = fs::read_to_string(&path)?;
+            let expected = match fs::read_to_string(path.with_extension(EXPECTED_EXTENSION)) {
+                Ok(expected) => parse_expected(&expected),
+                Err(_) => [None, None],
+            };
+            Ok(BatchInput {
+                path,
+                input,
+                expected,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Check {
+    Unknown,
+    Correct,
+    Wrong,
+}
+
+struct Cell {
+    answer: String,
+    time: String,
+    check: Check,
+}
+
+fn cell(result: &Result<Answer, SolveError>, time: Duration, expected: &Option<String>) -> Cell {
+    let answer = match result {
+        // Grids span multiple lines, which don't fit into a table.
+        Ok(answer) => answer
+            .to_string()
+            .lines()
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(err) => err.to_string(),
+    };
+    let check = match (result, expected) {
+        (_, None) => Check::Unknown,
+        (Ok(answer), Some(expected)) if *answer == Answer::from(expected.as_str()) => {
+            Check::Correct
+        }
+        (_, Some(_)) => Check::Wrong,
+    };
+
+    Cell {
+        answer,
+        time: dynamic_range_time_format(&time),
+        check,
+    }
+}
+
+fn print_cell(cell: &Cell, answer_width: usize) {
+    let answer = format!("{:answer_width$}", cell.answer);
+    print!(
+        "  {} {}  {:>11}",
+        match cell.check {
+            Check::Unknown => answer.blue(),
+            Check::Correct => answer.green(),
+            Check::Wrong => answer.red().bold(),
+        },
+        match cell.check {
+            Check::Unknown => " ".normal(),
+            Check::Correct => "✓".green(),
+            Check::Wrong => "✗".red().bold(),
+        },
+        cell.time
+    );
+}
+
+/// Runs a day over every input, printing a table of the answers and timings.
+///
+/// Returns whether no answer differed from its expected answer, and the records of all runs.
+pub fn run(day: u8, part: Part, inputs: &[BatchInput]) -> (bool, Vec<RunRecord>) {
+    let _span = tracing::info_span!("day", day, mode = "batch").entered();
+    println!(
+        "{} Day {} on {} inputs",
+        "Starting".green().bold(),
+        day,
+        inputs.len()
+    );
+    println!("{}", "-----------------------".green().bold());
+
+    let mut rows = vec![];
+    let mut records = vec![];
+    for batch_input in inputs {
+        let name = batch_input
+            .path
+            .file_name()
+            .map(|v| v.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let _span = tracing::info_span!("input", name).entered();
+        let input = &*input::normalize(&batch_input.input);
+        input::warn_non_ascii(input);
+
+        let run = solve_day(day, part.clone(), input);
+        records.push(run.record(day, input));
+
+        let one = run
+            .runs_one()
+            .then(|| cell(&run.one, run.one_t, &batch_input.expected[0]));
+        let two = run
+            .runs_two()
+            .then(|| cell(&run.two, run.two_t, &batch_input.expected[1]));
+        rows.push((name, one, two));
+    }
+
+    let name_width = rows.iter().map(|v| v.0.len()).max().unwrap_or(0).max(4);
+    let answer_width = rows
+        .iter()
+        .flat_map(|v| [&v.1, &v.2])
+        .flatten()
+        .map(|v| v.answer.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+
+    print!("{}", format!("{:name_width$}", "File").bold());
+    if part != Part::Two {
+        print!(
+            "{}",
+            format!("  {:answer_width$}    {:>11}", "Part 1", "Took").bold()
+        );
+    }
+    if part != Part::One {
+        print!(
+            "{}",
+            format!("  {:answer_width$}    {:>11}", "Part 2", "Took").bold()
+        );
+    }
+    println!();
+
+    let mut passed = true;
+    for ((name, one, two), batch_input) in rows.iter().zip(inputs) {
+        print!("{:name_width$}", name);
+        let mut notes = vec![];
+        for (i, cell) in [one, two].into_iter().enumerate() {
+            if let Some(cell) = cell {
+                print_cell(cell, answer_width);
+                if cell.check == Check::Wrong {
+                    passed = false;
+                    notes.push(format!(
+                        "part {} expected {}",
+                        i + 1,
+                        batch_input.expected[i].as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+        }
+        println!("  {}", notes.join(", ").red());
+    }
+
+    (passed, records)
+}
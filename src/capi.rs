@@ -0,0 +1,139 @@
+//! A C ABI for solving days from other languages.
+//!
+//! Build the shared library with `cargo build --release --features capi`,
+//! which also generates the matching declarations in `include/aoc24.h`.
+
+use crate::days::SolveError;
+use crate::{Part, input, solve_day};
+use std::ffi::{CString, c_char};
+use std::panic;
+use std::time::Duration;
+
+/// What an [`Aoc24Result`] holds, mirrors [`SolveError`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aoc24Kind {
+    Ok = 0,
+    NoSolution = 1,
+    InvalidInput = 2,
+    Unimplemented = 3,
+    UnknownVariant = 4,
+    /// The day panicked.
+    Panicked = 5,
+    /// The day, part or input passed to [`aoc24_solve`] isn't valid.
+    InvalidArguments = 6,
+}
+
+#[repr(C)]
+pub struct Aoc24Result {
+    pub kind: Aoc24Kind,
+    /// The answer as it would be submitted if `kind` is `Ok`, a description of the error otherwise.
+    pub answer: *mut c_char,
+    pub parse_ns: u64,
+    pub precompute_ns: u64,
+    pub part_ns: u64,
+}
+
+impl Aoc24Result {
+    fn new(kind: Aoc24Kind, answer: &str) -> Self {
+        // Answers never contain NUL, but a C string can't either way.
+        let answer = CString::new(answer.replace('\0', "")).unwrap_or_default();
+        Self {
+            kind,
+            answer: answer.into_raw(),
+            parse_ns: 0,
+            precompute_ns: 0,
+            part_ns: 0,
+        }
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos() as u64
+}
+
+fn solve(day: u8, part: u8, input: &[u8]) -> Aoc24Result {
+    if !(1..=25).contains(&day) {
+        return Aoc24Result::new(Aoc24Kind::InvalidArguments, "The day must be from 1 to 25.");
+    }
+    let part = match part {
+        1 => Part::One,
+        2 => Part::Two,
+        _ => return Aoc24Result::new(Aoc24Kind::InvalidArguments, "The part must be 1 or 2."),
+    };
+    let Ok(input) = std::str::from_utf8(input) else {
+        return Aoc24Result::new(Aoc24Kind::InvalidArguments, "The input isn't valid UTF-8.");
+    };
+
+    let input = &*input::normalize(input);
+    let Ok(run) = panic::catch_unwind(|| solve_day(day, part, input)) else {
+        return Aoc24Result::new(
+            Aoc24Kind::Panicked,
+            &format!("Day {} panicked on the input.", day),
+        );
+    };
+
+    let (answer, part_t) = if run.runs_one() {
+        (run.one, run.one_t)
+    } else {
+        (run.two, run.two_t)
+    };
+    let mut result = match answer {
+        Ok(answer) => Aoc24Result::new(Aoc24Kind::Ok, &answer.as_text()),
+        Err(err) => {
+            let kind = match err {
+                SolveError::NoSolution => Aoc24Kind::NoSolution,
+                SolveError::InvalidInput(_) => Aoc24Kind::InvalidInput,
+                SolveError::Unimplemented => Aoc24Kind::Unimplemented,
+                SolveError::UnknownVariant(_) => Aoc24Kind::UnknownVariant,
+                // Extra parts can't be selected through the C ABI.
+                SolveError::UnknownPart(_) => Aoc24Kind::InvalidArguments,
+            };
+            Aoc24Result::new(kind, &err.to_string())
+        }
+    };
+    result.parse_ns = nanos(run.init_t);
+    result.precompute_ns = nanos(run.pre_t);
+    result.part_ns = nanos(part_t);
+    result
+}
+
+/// Solves a part of a day, 1 or 2, on `len` bytes of UTF-8 input.
+///
+/// Never returns null. The result is owned by the caller and has to be freed with [`aoc24_free_result`].
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, it may only be null if `len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aoc24_solve(
+    day: u8,
+    part: u8,
+    input: *const u8,
+    len: usize,
+) -> *mut Aoc24Result {
+    let input = if input.is_null() {
+        &[]
+    } else {
+        // SAFETY: The caller guarantees `input` points to `len` bytes.
+        unsafe { std::slice::from_raw_parts(input, len) }
+    };
+    Box::into_raw(Box::new(solve(day, part, input)))
+}
+
+/// Frees a result returned by [`aoc24_solve`], including its answer. Does nothing for null.
+///
+/// # Safety
+///
+/// `result` must come from [`aoc24_solve`] and must not be used or freed again afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aoc24_free_result(result: *mut Aoc24Result) {
+    if result.is_null() {
+        return;
+    }
+    // SAFETY: The caller guarantees the result and its answer were allocated by `aoc24_solve`.
+    let result = unsafe { Box::from_raw(result) };
+    if !result.answer.is_null() {
+        drop(unsafe { CString::from_raw(result.answer) });
+    }
+}
@@ -0,0 +1,98 @@
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// The time spent in a named section of a part, summed over all times it was reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub label: &'static str,
+    pub total: Duration,
+    pub count: usize,
+}
+
+/// The label of the time between the last checkpoint and the end of the phase.
+pub const REST_LABEL: &str = "(rest)";
+
+struct Recorder {
+    phase: Option<&'static str>,
+    last: Instant,
+    phases: Vec<(&'static str, Vec<Checkpoint>)>,
+}
+
+impl Recorder {
+    fn record(&mut self, label: &'static str, now: Instant) {
+        let elapsed = now - self.last;
+        self.last = now;
+
+        let Some(phase) = self.phase else {
+            return;
+        };
+        let checkpoints = match self.phases.iter_mut().find(|(p, _)| *p == phase) {
+            Some((_, checkpoints)) => checkpoints,
+            None => {
+                self.phases.push((phase, vec![]));
+                &mut self.phases.last_mut().unwrap().1
+            }
+        };
+
+        match checkpoints.iter_mut().find(|v| v.label == label) {
+            Some(checkpoint) => {
+                checkpoint.total += elapsed;
+                checkpoint.count += 1;
+            }
+            None => checkpoints.push(Checkpoint {
+                label,
+                total: elapsed,
+                count: 1,
+            }),
+        }
+    }
+}
+
+thread_local! {
+    static RECORDER: RefCell<Recorder> = RefCell::new(Recorder {
+        phase: None,
+        last: Instant::now(),
+        phases: vec![],
+    });
+}
+
+/// Starts collecting checkpoints for a phase, like `"one"`.
+pub fn begin(phase: &'static str) {
+    RECORDER.with_borrow_mut(|r| {
+        r.phase = Some(phase);
+        r.phases.retain(|(p, _)| *p != phase);
+        r.last = Instant::now();
+    });
+}
+
+/// Marks the end of a section of the current phase.
+///
+/// The time since the previous checkpoint, or the start of the phase, is added to `label`.
+/// Does nothing outside of a phase.
+pub fn checkpoint(label: &'static str) {
+    let now = Instant::now();
+    RECORDER.with_borrow_mut(|r| r.record(label, now));
+}
+
+/// Stops collecting checkpoints for the current phase.
+///
+/// If checkpoints were reached, the remaining time is recorded as [`REST_LABEL`].
+pub fn end() {
+    let now = Instant::now();
+    RECORDER.with_borrow_mut(|r| {
+        if let Some(phase) = r.phase
+            && r.phases.iter().any(|(p, _)| *p == phase)
+        {
+            r.record(REST_LABEL, now);
+        }
+        r.phase = None;
+    });
+}
+
+/// Takes the checkpoints of a finished phase.
+pub fn take(phase: &'static str) -> Vec<Checkpoint> {
+    RECORDER.with_borrow_mut(|r| match r.phases.iter().position(|(p, _)| *p == phase) {
+        Some(i) => r.phases.remove(i).1,
+        None => vec![],
+    })
+}
@@ -0,0 +1,179 @@
+use crate::days::{DEFAULT_VARIANT, Day, DayImpl, SolveError, VariantRun, input_generator};
+use crate::fuzz::Rng;
+use crate::{dynamic_range_time_format, format_solution, input, params};
+use aoc_macro::{match_and_crosscheck_day, match_and_crosscheck_test_day};
+use colored::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct CrosscheckSettings {
+    /// Real inputs, by name.
+    pub inputs: Vec<(String, String)>,
+    /// The number of inputs taken from the day's input generator, if it has one.
+    pub generated: usize,
+    pub seed: u64,
+    pub max_size: usize,
+}
+
+/// The total time a variant took over all inputs it was run on.
+struct Timing {
+    part: u8,
+    name: &'static str,
+    total: Duration,
+    runs: usize,
+}
+
+/// Runs every variant of a day on one input, or `None` if parsing it panicked.
+fn run_variants(day: u8, input: Option<&str>) -> Option<Vec<VariantRun>> {
+    panic::catch_unwind(AssertUnwindSafe(|| match input {
+        Some(input) => match_and_crosscheck_day!(),
+        None => match_and_crosscheck_test_day!(),
+    }))
+    .ok()
+}
+
+/// Checks that the variants of each part agree, printing the ones that don't.
+/// Parts whose variants are all unimplemented are skipped.
+fn compare(source: &str, runs: &[VariantRun], timings: &mut Vec<Timing>) -> bool {
+    let mut agree = true;
+
+    for part in [1, 2] {
+        let part_runs: Vec<&VariantRun> = runs
+            .iter()
+            .filter(|v| v.part == part && v.result != Some(Err(SolveError::Unimplemented)))
+            .collect();
+        let Some(first) = part_runs.first() else {
+            continue;
+        };
+
+        for run in &part_runs {
+            match timings
+                .iter_mut()
+                .find(|v| v.part == part && v.name == run.name)
+            {
+                Some(timing) => {
+                    timing.total += run.time;
+                    timing.runs += 1;
+                }
+                None => timings.push(Timing {
+                    part,
+                    name: run.name,
+                    total: run.time,
+                    runs: 1,
+                }),
+            }
+        }
+
+        if part_runs.iter().any(|v| v.result != first.result) {
+            agree = false;
+            println!(
+                "{}",
+                format!("{}: the variants of part {} disagree", source, part)
+                    .red()
+                    .bold()
+            );
+            for run in &part_runs {
+                match &run.result {
+                    Some(result) => println!("\t{}: {}", run.name, format_solution(result)),
+                    None => println!("\t{}: {}", run.name, "panicked".red().bold()),
+                }
+            }
+        }
+    }
+
+    agree
+}
+
+/// Runs all variants of a day on its example, the given inputs and generated inputs,
+/// reporting disagreements and comparing the timings of the variants.
+///
+/// Returns whether all variants agreed everywhere.
+pub fn crosscheck(day: u8, settings: &CrosscheckSettings) -> bool {
+    let _span = tracing::info_span!("day", day, mode = "crosscheck").entered();
+
+    let Some(example) = run_variants(day, None) else {
+        println!("{}", "Parsing the example panicked.".red().bold());
+        return false;
+    };
+    if example.iter().all(|v| v.name == DEFAULT_VARIANT) {
+        println!("Day {} has no variants to compare.", day);
+        return true;
+    }
+
+    println!("{} Day {}", "Crosschecking".green().bold(), day);
+    println!("{}", "-----------------------".green().bold());
+
+    // Panics are reported as disagreements instead of being printed where they happen.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut timings = vec![];
+    let mut sources = 1;
+    let mut agree = compare("example", &example, &mut timings);
+
+    for (name, input) in &settings.inputs {
+        let input = &*input::normalize(input);
+        sources += 1;
+        agree &= match run_variants(day, Some(input)) {
+            Some(runs) => compare(name, &runs, &mut timings),
+            None => {
+                println!("{}", format!("{}: parsing panicked", name).red().bold());
+                false
+            }
+        };
+    }
+
+    if let Some(generator) = input_generator(day) {
+        for i in 0..settings.generated {
+            let seed = settings.seed.wrapping_add(i as u64);
+            let mut rng = Rng::new(seed);
+            let size = 1 + rng.below(settings.max_size as u64) as usize;
+            let input = (generator.generate)(&mut rng, size);
+            let name = format!("generated input with seed {} and size {}", seed, size);
+            sources += 1;
+            agree &= match params::with_generated(|| run_variants(day, Some(&input))) {
+                Some(runs) => compare(&name, &runs, &mut timings),
+                None => {
+                    println!("{}", format!("{}: parsing panicked", name).red().bold());
+                    false
+                }
+            };
+        }
+    }
+
+    panic::set_hook(default_hook);
+
+    if agree {
+        println!(
+            "{}",
+            format!("All variants agree on {} inputs.", sources)
+                .green()
+                .bold()
+        );
+    }
+
+    println!("{}:", "Timings".green().bold());
+    for timing in &timings {
+        let default = timings
+            .iter()
+            .find(|v| v.part == timing.part && v.name == DEFAULT_VARIANT);
+        let relative = match default {
+            Some(default) if default.name != timing.name && !default.total.is_zero() => format!(
+                " ({:.2}× default)",
+                timing.total.as_secs_f64() / default.total.as_secs_f64()
+            ),
+            _ => String::new(),
+        };
+        println!(
+            "\tPart {} {:12} {} total over {} inputs{}",
+            timing.part,
+            format!("{}:", timing.name),
+            dynamic_range_time_format(&timing.total).bold().blue(),
+            timing.runs,
+            relative
+        );
+    }
+
+    agree
+}
@@ -0,0 +1,444 @@
+use crate::days::{Answer, SolveError};
+use crate::history;
+use crate::report::{self, PartStatus, TITLES};
+use crate::tui::RawTerminal;
+use crate::{Part, dynamic_range_time_format, extra_part_names, input, puzzle, solve_day};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    queue,
+    style::{Print, Stylize},
+    terminal::{self, ClearType},
+};
+use std::fs;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The number of runs of a benchmark.
+const BENCH_RUNS: usize = 10;
+
+/// The cached input of a day, as downloaded by the `auto` command.
+fn cached_input(day: u8) -> Option<String> {
+    fs::read_to_string(format!("./.aoc24_cache/input{:02}.txt", day)).ok()
+}
+
+/// What the dashboard knows about a day.
+struct DayState {
+    /// The example results, `None` if testing panicked.
+    status: Option<[PartStatus; 2]>,
+    cached_input: bool,
+    /// The answers of the last run in this session.
+    answers: [Option<Result<Answer, SolveError>>; 2],
+    /// The times of the last run, from the history until the day is run in this session.
+    times: [Option<Duration>; 2],
+}
+
+enum View {
+    Days,
+    /// A scrollable text, like the puzzle or the answers of a day.
+    Text {
+        title: String,
+        lines: Vec<String>,
+        scroll: usize,
+    },
+}
+
+struct Dashboard {
+    days: Vec<DayState>,
+    selected: usize,
+    view: View,
+    message: String,
+}
+
+/// The message of the last panic, stored by the panic hook of the dashboard.
+static PANIC_MESSAGE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Runs a day, turning a panic into `None`.
+fn catch<T>(f: impl FnOnce() -> T) -> Option<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).ok()
+}
+
+/// Takes the message of the last panic, to show it in the dashboard.
+fn panic_message() -> String {
+    PANIC_MESSAGE
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .take()
+        .unwrap_or_default()
+}
+
+/// The first line of an answer, as grids and bitmaps span multiple lines.
+fn answer_summary(answer: &Option<Result<Answer, SolveError>>) -> String {
+    match answer {
+        Some(Ok(Answer::Bitmap(_))) => "bitmap, press a".to_string(),
+        Some(Ok(Answer::Grid(_))) => "grid, press a".to_string(),
+        Some(Ok(answer)) => answer.to_string(),
+        Some(Err(err)) => err.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+/// Draws bitmaps with blocks and grids as they are, other answers take a single line.
+fn render_answer(answer: &Result<Answer, SolveError>) -> Vec<String> {
+    match answer {
+        Ok(Answer::Bitmap(bm)) => bm
+            .iter()
+            .map(|row| row.iter().map(|v| if *v { '█' } else { ' ' }).collect())
+            .collect(),
+        Ok(Answer::Grid(grid)) => grid.iter().map(|row| row.iter().collect()).collect(),
+        Ok(answer) => vec![answer.to_string()],
+        Err(err) => vec![err.to_string()],
+    }
+}
+
+impl Dashboard {
+    fn new() -> Self {
+        let records = history::load().unwrap_or_default();
+        let days = (1..=25)
+            .map(|day| {
+                let last = records.iter().rev().find(|v| v.day == day);
+                DayState {
+                    status: catch(|| report::example_status(day)),
+                    cached_input: cached_input(day).is_some(),
+                    answers: [None, None],
+                    times: [
+                        last.and_then(|v| v.one_ns).map(Duration::from_nanos),
+                        last.and_then(|v| v.two_ns).map(Duration::from_nanos),
+                    ],
+                }
+            })
+            .collect();
+
+        Self {
+            days,
+            selected: 0,
+            view: View::Days,
+            message: String::new(),
+        }
+    }
+
+    fn day(&self) -> u8 {
+        self.selected as u8 + 1
+    }
+
+    fn test(&mut self) {
+        let day = self.day();
+        let status = catch(|| report::example_status(day));
+        self.message = match status {
+            Some(status) => format!(
+                "Day {} example: part 1 {:?}, part 2 {:?}",
+                day, status[0], status[1]
+            ),
+            None => format!("Day {} panicked on its example: {}", day, panic_message()),
+        };
+        self.days[self.selected].status = status;
+    }
+
+    /// Runs the selected day on its cached input and records the run in the history.
+    fn run(&mut self) {
+        let day = self.day();
+        let Some(input) = cached_input(day) else {
+            self.message = format!(
+                "Day {} has no cached input, download it with `aoc24 {} auto`.",
+                day, day
+            );
+            return;
+        };
+        let input = &*input::normalize(&input);
+
+        let Some(run) = catch(|| solve_day(day, Part::Both, input)) else {
+            self.message = format!("Day {} panicked: {}", day, panic_message());
+            return;
+        };
+        if let Err(err) = history::append(&run.record(day, input)) {
+            self.message = format!("Couldn't record the run: {}", err);
+        } else {
+            self.message = format!(
+                "Day {} took {} to parse and {} to precompute.",
+                day,
+                dynamic_range_time_format(&run.init_t),
+                dynamic_range_time_format(&run.pre_t)
+            );
+        }
+
+        let state = &mut self.days[self.selected];
+        state.cached_input = true;
+        state.times = [Some(run.one_t), Some(run.two_t)];
+        state.answers = [Some(run.one), Some(run.two)];
+    }
+
+    /// Runs both parts and the extra parts of the selected day on its cached input several times
+    /// and shows the median times.
+    fn bench(&mut self) {
+        let day = self.day();
+        let Some(input) = cached_input(day) else {
+            self.message = format!("Day {} has no cached input to benchmark.", day);
+            return;
+        };
+        let input = &*input::normalize(&input);
+
+        let parts = std::iter::once(Part::Both).chain(
+            extra_part_names(day)
+                .into_iter()
+                .map(|v| Part::Extra(v.to_string())),
+        );
+        let mut times: Vec<(String, Vec<Duration>)> = vec![];
+        for part in parts {
+            let first = times.len();
+            for _ in 0..BENCH_RUNS {
+                let Some(run) = catch(|| solve_day(day, part.clone(), input)) else {
+                    self.message = format!("Day {} panicked: {}", day, panic_message());
+                    return;
+                };
+                for (i, (name, time)) in run.part_times().into_iter().enumerate() {
+                    match times.get_mut(first + i) {
+                        Some(part_times) => part_times.1.push(time),
+                        None => times.push((name, vec![time])),
+                    }
+                }
+            }
+        }
+        for part in &mut times {
+            part.1.sort();
+        }
+
+        let medians: Vec<String> = times
+            .iter()
+            .map(|(name, times)| {
+                format!(
+                    "{} median {} (min {})",
+                    name,
+                    dynamic_range_time_format(&times[BENCH_RUNS / 2]),
+                    dynamic_range_time_format(&times[0])
+                )
+            })
+            .collect();
+        self.message = format!(
+            "Day {} over {} runs: {}",
+            day,
+            BENCH_RUNS,
+            medians.join(", ")
+        );
+        self.days[self.selected].times = [
+            Some(times[0].1[BENCH_RUNS / 2]),
+            Some(times[1].1[BENCH_RUNS / 2]),
+        ];
+    }
+
+    fn show_answers(&mut self) {
+        let state = &self.days[self.selected];
+        if state.answers.iter().all(|v| v.is_none()) {
+            self.message = "Run the day first.".to_string();
+            return;
+        }
+
+        let mut lines = vec![];
+        for (i, answer) in state.answers.iter().enumerate() {
+            if let Some(answer) = answer {
+                lines.push(format!("Part {}:", i + 1));
+                lines.extend(render_answer(answer));
+                lines.push(String::new());
+            }
+        }
+        self.view = View::Text {
+            title: format!("Day {} answers", self.day()),
+            lines,
+            scroll: 0,
+        };
+    }
+
+    fn show_puzzle(&mut self) {
+        let day = self.day();
+        match puzzle::load(day) {
+            Some(text) => {
+                self.view = View::Text {
+                    title: format!("Day {}: {}", day, TITLES[self.selected]),
+                    lines: text.lines().map(|v| v.to_string()).collect(),
+                    scroll: 0,
+                }
+            }
+            None => {
+                self.message = format!(
+                    "No cached puzzle at {}, download it with `aoc24 {} auto`.",
+                    puzzle::cache_path(day).display(),
+                    day
+                )
+            }
+        }
+    }
+
+    /// Shows that the selected day is busy, as runs block the dashboard.
+    fn busy(&mut self, out: &mut impl Write, action: &str) -> std::io::Result<()> {
+        self.message = format!("{} day {}...", action, self.day());
+        self.draw(out)
+    }
+
+    /// Handles a key press, returns false if the dashboard should be closed.
+    fn handle_key(&mut self, key: KeyEvent, out: &mut impl Write) -> std::io::Result<bool> {
+        if let View::Text { scroll, .. } = &mut self.view {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.view = View::Days,
+                KeyCode::Down | KeyCode::Char('j') => *scroll += 1,
+                KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+                KeyCode::PageDown | KeyCode::Char(' ') => *scroll += 20,
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(20),
+                _ => {}
+            }
+            return Ok(true);
+        }
+
+        self.message.clear();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(24),
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = 24,
+            KeyCode::Char('t') => {
+                self.busy(out, "Testing")?;
+                self.test();
+            }
+            KeyCode::Char('r') => {
+                self.busy(out, "Running")?;
+                self.run();
+            }
+            KeyCode::Char('b') => {
+                self.busy(out, "Benchmarking")?;
+                self.bench();
+            }
+            KeyCode::Char('a') | KeyCode::Enter => self.show_answers(),
+            KeyCode::Char('p') => self.show_puzzle(),
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn day_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "{:>3}  {:24}  {:7}  {:5}  {:20} {:>11}  {:20} {:>11}",
+                "Day", "Title", "Example", "Input", "Part 1", "Took", "Part 2", "Took"
+            )
+            .bold()
+            .to_string(),
+        ];
+
+        for (i, state) in self.days.iter().enumerate() {
+            let example = match state.status {
+                Some(status) => format!(
+                    "{} {}",
+                    report::status_symbol(status[0]),
+                    report::status_symbol(status[1])
+                ),
+                None => "panic".to_string(),
+            };
+            let time = |time: &Option<Duration>| match time {
+                Some(time) => dynamic_range_time_format(time),
+                None => "-".to_string(),
+            };
+            let line = format!(
+                "{:>3}  {:24}  {:7}  {:5}  {:20} {:>11}  {:20} {:>11}",
+                i + 1,
+                TITLES[i],
+                example,
+                if state.cached_input { "yes" } else { "no" },
+                answer_summary(&state.answers[0])
+                    .chars()
+                    .take(20)
+                    .collect::<String>(),
+                time(&state.times[0]),
+                answer_summary(&state.answers[1])
+                    .chars()
+                    .take(20)
+                    .collect::<String>(),
+                time(&state.times[1]),
+            );
+            lines.push(if i == self.selected {
+                line.reverse().to_string()
+            } else {
+                line
+            });
+        }
+
+        lines
+    }
+
+    fn draw(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (width as usize, height as usize);
+
+        let (lines, footer) = match &self.view {
+            View::Days => (
+                self.day_lines(),
+                "↑/↓ select  r run  t test  b benchmark  a answers  p puzzle  q quit",
+            ),
+            View::Text {
+                title,
+                lines,
+                scroll,
+            } => (
+                std::iter::once(title.clone().bold().to_string())
+                    .chain(lines.iter().skip(*scroll).cloned())
+                    .collect(),
+                "↑/↓ scroll  q back",
+            ),
+        };
+        let footer = if self.message.is_empty() {
+            footer.to_string()
+        } else {
+            self.message.clone()
+        };
+
+        queue!(out, terminal::Clear(ClearType::All))?;
+        for (row, line) in lines.iter().take(height.saturating_sub(1)).enumerate() {
+            queue!(out, cursor::MoveTo(0, row as u16), Print(line))?;
+        }
+        queue!(
+            out,
+            cursor::MoveTo(0, height.saturating_sub(1) as u16),
+            Print(footer.chars().take(width).collect::<String>().dim())
+        )?;
+        out.flush()
+    }
+
+    fn run_loop(&mut self) -> std::io::Result<()> {
+        let mut out = std::io::stdout();
+        loop {
+            self.draw(&mut out)?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !self.handle_key(key, &mut out)?
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Opens the interactive dashboard of all days.
+pub fn open() -> std::io::Result<()> {
+    // Panics are shown in the dashboard instead of being printed over it.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+        *PANIC_MESSAGE.lock().unwrap_or_else(|err| err.into_inner()) = Some(info.to_string());
+    }));
+
+    println!("Testing all days...");
+    let result = panic::catch_unwind(|| {
+        let mut dashboard = Dashboard::new();
+        let _terminal = RawTerminal::enter()?;
+        dashboard.run_loop()
+    });
+
+    panic::set_hook(default_hook);
+    match result {
+        Ok(result) => result,
+        Err(payload) => {
+            // The terminal is restored by now, so the panic of the dashboard itself can be printed.
+            eprintln!("{}", panic_message());
+            panic::resume_unwind(payload)
+        }
+    }
+}
@@ -1,12 +1,15 @@
-use super::{Answer, Day, DayImpl};
+use super::{Answer, Day, DayImpl, InputGenerator, Metamorphic, PartFn, SolveError};
+use crate::fuzz::Rng;
+use crate::metamorphic::Transform;
+use std::collections::HashMap;
 
 const CURRENT_DAY: u8 = 1;
 
 type Data = Vec<[u64; 2]>;
-impl DayImpl<Data> for Day<CURRENT_DAY> {
-    fn init_test() -> (Self, Data) {
-        Self::init(include_str!("test_inputs/test01.txt"))
-    }
+impl DayImpl<'_, Data> for Day<CURRENT_DAY> {
+    const MUTATES_DATA: bool = false;
+
+    const EXAMPLE: &'static str = include_str!("test_inputs/test01.txt");
 
     fn expected_results() -> (Answer, Answer) {
         (Answer::Number(0), Answer::Number(0))
@@ -28,29 +31,64 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
         )
     }
 
-    fn one(&self, data: &mut Data) -> Answer {
+    fn one(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
         let mut left = data.iter().map(|v| v[0]).collect::<Vec<u64>>();
         let mut right = data.iter().map(|v| v[1]).collect::<Vec<u64>>();
 
         left.sort();
         right.sort();
 
-        Answer::Number(
+        Ok(Answer::Number(
             left.into_iter()
-                .zip(right.into_iter())
+                .zip(right)
                 .map(|(a, b)| a.abs_diff(b))
                 .sum(),
-        )
+        ))
     }
 
-    fn two(&self, data: &mut Data) -> Answer {
+    fn two(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
         let left = data.iter().map(|v| v[0]).collect::<Vec<u64>>();
         let right = data.iter().map(|v| v[1]).collect::<Vec<u64>>();
 
-        Answer::Number(
+        Ok(Answer::Number(
             left.iter()
                 .map(|v| v * right.iter().filter(|n| *n == v).count() as u64)
                 .sum(),
-        )
+        ))
+    }
+
+    fn two_variants() -> Vec<(&'static str, PartFn<Self, Data, ()>)> {
+        vec![("hashmap", Self::two_hashmap)]
+    }
+}
+
+impl Day<CURRENT_DAY> {
+    /// Counts the right list once instead of scanning it for every number on the left.
+    fn two_hashmap(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
+        let mut counts: HashMap<u64, u64> = HashMap::new();
+        for v in data.iter() {
+            *counts.entry(v[1]).or_default() += 1;
+        }
+
+        Ok(Answer::Number(
+            data.iter()
+                .map(|v| v[0] * counts.get(&v[0]).copied().unwrap_or(0))
+                .sum(),
+        ))
+    }
+}
+
+impl Metamorphic for Day<CURRENT_DAY> {
+    const TRANSFORMS: &'static [Transform] = &[Transform::ShuffleLines];
+}
+
+impl InputGenerator for Day<CURRENT_DAY> {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        // Small numbers make the similarity score of part two count duplicates.
+        let max = if rng.chance(0.5) { 10 } else { 100000 };
+        (0..size)
+            .map(|_| format!("{}   {}", rng.below(max), rng.below(max)))
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
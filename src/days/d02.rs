@@ -1,4 +1,6 @@
-use super::{Answer, Day, DayImpl};
+use super::{Answer, Day, DayImpl, ExtraPart, InputGenerator, Metamorphic, SolveError};
+use crate::fuzz::Rng;
+use crate::metamorphic::Transform;
 
 const CURRENT_DAY: u8 = 2;
 
@@ -45,8 +47,22 @@ impl Report {
      * direction to be wrong.
      */
     pub fn is_safe_any_way(&self, tolerance: usize) -> bool {
-        return self.is_safe(Some(Direction::Decreasing), tolerance)
-            || self.is_safe(Some(Direction::Increasing), tolerance);
+        self.is_safe(Some(Direction::Decreasing), tolerance)
+            || self.is_safe(Some(Direction::Increasing), tolerance)
+    }
+
+    /// Whether the report is safe after removing up to `removals` levels, trying every combination.
+    pub fn is_safe_removing(&self, removals: usize) -> bool {
+        if self.is_safe(None, 0) {
+            return true;
+        }
+
+        removals > 0
+            && (0..self.0.len()).any(|i| {
+                let mut levels = self.0.clone();
+                levels.remove(i);
+                Report(levels).is_safe_removing(removals - 1)
+            })
     }
 
     fn safe_comparison(a: i8, b: i8, direction: &mut Option<Direction>) -> bool {
@@ -67,7 +83,7 @@ impl Report {
             _ => {}
         }
 
-        return true;
+        true
     }
 }
 
@@ -83,10 +99,10 @@ impl From<&str> for Report {
 }
 
 type Data = Vec<Report>;
-impl DayImpl<Data> for Day<CURRENT_DAY> {
-    fn init_test() -> (Self, Data) {
-        Self::init(include_str!("test_inputs/test02.txt"))
-    }
+impl DayImpl<'_, Data> for Day<CURRENT_DAY> {
+    const MUTATES_DATA: bool = false;
+
+    const EXAMPLE: &'static str = include_str!("test_inputs/test02.txt");
 
     fn expected_results() -> (Answer, Answer) {
         (Answer::Number(2), Answer::Number(4))
@@ -96,7 +112,7 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
         (Self {}, input.lines().map(|l| l.into()).collect())
     }
 
-    fn one(&self, data: &mut Data) -> Answer {
+    fn one(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
         let mut safe_count = 0;
         for report in data {
             if report.is_safe(None, 0) {
@@ -104,10 +120,10 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
             }
         }
 
-        return Answer::Number(safe_count);
+        Ok(Answer::Number(safe_count))
     }
 
-    fn two(&self, data: &mut Data) -> Answer {
+    fn two(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
         let mut safe_count = 0;
         for report in data {
             if report.is_safe_any_way(1) {
@@ -115,6 +131,53 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
             }
         }
 
-        return Answer::Number(safe_count);
+        Ok(Answer::Number(safe_count))
+    }
+
+    fn extra_parts() -> Vec<ExtraPart<Self, Data, ()>> {
+        vec![ExtraPart {
+            name: "two-removed",
+            solve: |_, data, _| {
+                Ok(data
+                    .iter()
+                    .filter(|report| report.is_safe_removing(2))
+                    .count()
+                    .into())
+            },
+            example: None,
+            expected: Answer::Number(6),
+        }]
+    }
+}
+
+impl Metamorphic for Day<CURRENT_DAY> {
+    const TRANSFORMS: &'static [Transform] = &[Transform::ShuffleLines];
+}
+
+impl InputGenerator for Day<CURRENT_DAY> {
+    /// Mostly produces reports that are safe or almost safe, which are the interesting ones.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let increasing = rng.chance(0.5);
+                let mut level = rng.range(1, 99) as i64;
+                let mut levels = vec![level];
+                for _ in 1..rng.range(1, 8) {
+                    let step = if rng.chance(0.8) {
+                        rng.range(1, 3) as i64
+                    } else {
+                        rng.range(0, 6) as i64 - 2
+                    };
+                    level = (level + if increasing { step } else { -step }).clamp(1, 99);
+                    levels.push(level);
+                }
+                levels
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
@@ -1,15 +1,15 @@
-use super::{Answer, Day, DayImpl};
+use super::{Answer, Day, DayImpl, SolveError};
 use regex::Regex;
 
 const CURRENT_DAY: u8 = 3;
 
 #[derive(Clone, Debug)]
-pub struct Command {
-    command: String,
+pub struct Command<'a> {
+    command: &'a str,
     parameters: Vec<u64>,
 }
 
-impl Command {
+impl Command<'_> {
     pub fn run(&self) -> u64 {
         if self.command != "mul" || self.parameters.len() < 2 {
             return 0;
@@ -19,9 +19,9 @@ impl Command {
 }
 
 #[derive(Clone, Debug)]
-pub struct Program(Vec<Command>);
+pub struct Program<'a>(Vec<Command<'a>>);
 
-impl Program {
+impl Program<'_> {
     pub fn do_all_multiplications(&self) -> u64 {
         self.0.iter().map(|v| v.run()).sum()
     }
@@ -45,14 +45,14 @@ impl Program {
     }
 }
 
-impl From<&str> for Program {
-    fn from(value: &str) -> Self {
+impl<'a> From<&'a str> for Program<'a> {
+    fn from(value: &'a str) -> Self {
         let pattern: Regex = Regex::new(r"(do|don't|mul)\(((?:\d+,?)*)\)").unwrap();
 
         let mut commands = vec![];
         for (_, [command, parameters]) in pattern.captures_iter(value).map(|c| c.extract()) {
             commands.push(Command {
-                command: command.to_string(),
+                command,
                 parameters: parameters
                     .split(",")
                     .map(|v| v.parse().unwrap_or(0))
@@ -64,25 +64,25 @@ impl From<&str> for Program {
     }
 }
 
-type Data = Program;
-impl DayImpl<Data> for Day<CURRENT_DAY> {
-    fn init_test() -> (Self, Data) {
-        Self::init(include_str!("test_inputs/test03.txt"))
-    }
+type Data<'a> = Program<'a>;
+impl<'a> DayImpl<'a, Data<'a>> for Day<CURRENT_DAY> {
+    const MUTATES_DATA: bool = false;
+
+    const EXAMPLE: &'static str = include_str!("test_inputs/test03.txt");
 
     fn expected_results() -> (Answer, Answer) {
         (Answer::Number(161), Answer::Number(0))
     }
 
-    fn init(input: &str) -> (Self, Data) {
+    fn init(input: &'a str) -> (Self, Data<'a>) {
         (Self {}, input.into())
     }
 
-    fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.do_all_multiplications())
+    fn one(&self, data: &mut Data<'a>, _: &()) -> Result<Answer, SolveError> {
+        Ok(Answer::Number(data.do_all_multiplications()))
     }
 
-    fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.run())
+    fn two(&self, data: &mut Data<'a>, _: &()) -> Result<Answer, SolveError> {
+        Ok(Answer::Number(data.run()))
     }
 }
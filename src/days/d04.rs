@@ -1,12 +1,17 @@
-use super::{Answer, Day, DayImpl};
+use super::{Answer, Day, DayImpl, ExtraPart, Metamorphic, SolveError, Tool};
+use crate::metamorphic::Transform;
+use clap::{Arg, SubCommand};
 
 const CURRENT_DAY: u8 = 4;
 
+/// A word found in the wall: the position of its first letter and its direction, as steps in x and y.
+type Found = ((usize, usize), (isize, isize));
+
 #[derive(Debug, Clone)]
-pub struct LetterWall(Vec<Vec<char>>);
+pub struct LetterWall<'a>(Vec<&'a [u8]>);
 
-impl LetterWall {
-    fn at(&self, x: usize, y: usize) -> Option<&char> {
+impl LetterWall<'_> {
+    fn at(&self, x: usize, y: usize) -> Option<&u8> {
         let row = self.0.get(y);
 
         if let Some(row) = row {
@@ -28,7 +33,7 @@ impl LetterWall {
         );
         
         if let (
-            Some(&'X'), Some(&'M'), Some(&'A'), Some(&'S'),
+            Some(&b'X'), Some(&b'M'), Some(&b'A'), Some(&b'S'),
             _, _, _, _,
             _, _, _, _,
             _, _, _, _
@@ -37,7 +42,7 @@ impl LetterWall {
         }
         
         if let (
-            Some('S'), Some('A'), Some('M'), Some('X'),
+            Some(b'S'), Some(b'A'), Some(b'M'), Some(b'X'),
             _, _, _, _,
             _, _, _, _,
             _, _, _, _
@@ -46,55 +51,55 @@ impl LetterWall {
         }
         
         if let (
-            Some('X'), _, _, _,
-            Some('M'), _, _, _,
-            Some('A'), _, _, _,
-            Some('S'), _, _, _
+            Some(b'X'), _, _, _,
+            Some(b'M'), _, _, _,
+            Some(b'A'), _, _, _,
+            Some(b'S'), _, _, _
         ) = map {
             directions += 1;
         }
         
         if let (
-            Some('S'), _, _, _,
-            Some('A'), _, _, _,
-            Some('M'), _, _, _,
-            Some('X'), _, _, _
+            Some(b'S'), _, _, _,
+            Some(b'A'), _, _, _,
+            Some(b'M'), _, _, _,
+            Some(b'X'), _, _, _
         ) = map {
             directions += 1;
         }
         
         if let (
-            Some('X'), _, _, _,
-            _, Some('M'), _, _,
-            _, _, Some('A'), _,
-            _, _, _, Some('S')
+            Some(b'X'), _, _, _,
+            _, Some(b'M'), _, _,
+            _, _, Some(b'A'), _,
+            _, _, _, Some(b'S')
         ) = map {
             directions += 1;
         }
         
         if let (
-            Some('S'), _, _, _,
-            _, Some('A'), _, _,
-            _, _, Some('M'), _,
-            _, _, _, Some('X')
+            Some(b'S'), _, _, _,
+            _, Some(b'A'), _, _,
+            _, _, Some(b'M'), _,
+            _, _, _, Some(b'X')
         ) = map {
             directions += 1;
         }
         
         if let (
-            _, _, _, Some('X'),
-            _, _, Some('M'), _,
-            _, Some('A'), _, _,
-            Some('S'), _, _, _
+            _, _, _, Some(b'X'),
+            _, _, Some(b'M'), _,
+            _, Some(b'A'), _, _,
+            Some(b'S'), _, _, _
         ) = map {
             directions += 1;
         }
         
         if let (
-            _, _, _, Some('S'),
-            _, _, Some('A'), _,
-            _, Some('M'), _, _,
-            Some('X'), _, _, _
+            _, _, _, Some(b'S'),
+            _, _, Some(b'A'), _,
+            _, Some(b'M'), _, _,
+            Some(b'X'), _, _, _
         ) = map {
             directions += 1;
         }
@@ -103,6 +108,7 @@ impl LetterWall {
     }
 
     #[rustfmt::skip]
+    #[allow(clippy::match_like_matches_macro)]
     fn is_cross_mas(&self, x: usize, y: usize) -> bool {
         match(
             self.at( x, y), self.at( x+1, y), self.at( x+2, y),
@@ -110,24 +116,24 @@ impl LetterWall {
             self.at( x, y+2), self.at( x+1, y+2), self.at( x+2, y+2)
         ) {
             (
-                Some('M'), _,         Some('S'),
-                _,         Some('A'), _,
-                Some('M'), _,         Some('S'),
+                Some(b'M'), _,         Some(b'S'),
+                _,         Some(b'A'), _,
+                Some(b'M'), _,         Some(b'S'),
             ) => true,
             (
-                Some('S'), _,         Some('S'),
-                _,         Some('A'), _,
-                Some('M'), _,         Some('M'),
+                Some(b'S'), _,         Some(b'S'),
+                _,         Some(b'A'), _,
+                Some(b'M'), _,         Some(b'M'),
             ) => true,
             (
-                Some('M'), _,         Some('M'),
-                _,         Some('A'), _,
-                Some('S'), _,         Some('S'),
+                Some(b'M'), _,         Some(b'M'),
+                _,         Some(b'A'), _,
+                Some(b'S'), _,         Some(b'S'),
             ) => true,
             (
-                Some('S'), _,         Some('M'),
-                _,         Some('A'), _,
-                Some('S'), _,         Some('M'),
+                Some(b'S'), _,         Some(b'M'),
+                _,         Some(b'A'), _,
+                Some(b'S'), _,         Some(b'M'),
             ) => true,
             _ => false
         }
@@ -141,7 +147,7 @@ impl LetterWall {
                 count += self.get_xmas_direction_count(x, y);
             }
         }
-        return count as u64;
+        count
     }
 
     pub fn count_cross_mas(&self) -> u64 {
@@ -153,36 +159,160 @@ impl LetterWall {
                 }
             }
         }
-        return count as u64;
+        count
+    }
+
+    /// The 8 directions a word can be written in, as steps in x and y.
+    const DIRECTIONS: [(isize, isize); 8] = [
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+        (0, -1),
+        (1, -1),
+    ];
+
+    /// Every occurrence of a word in any direction.
+    ///
+    /// A palindrome reads the same in opposite directions, so it is only searched
+    /// in the first half of them to find each occurrence once, a single letter in one.
+    pub fn find_word(&self, word: &[u8]) -> Vec<Found> {
+        let directions = match word.len() {
+            0 | 1 => &Self::DIRECTIONS[..1],
+            _ if word.iter().eq(word.iter().rev()) => &Self::DIRECTIONS[..4],
+            _ => &Self::DIRECTIONS[..],
+        };
+        let mut found = vec![];
+        for y in 0..self.0.len() {
+            for x in 0..self.0[y].len() {
+                for &(dx, dy) in directions {
+                    let matches = word.iter().enumerate().all(|(i, letter)| {
+                        let i = i as isize;
+                        x.checked_add_signed(dx * i)
+                            .zip(y.checked_add_signed(dy * i))
+                            .and_then(|(x, y)| self.at(x, y))
+                            == Some(letter)
+                    });
+                    if matches {
+                        found.push(((x, y), (dx, dy)));
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Draws the wall with only the letters of the given words, like the puzzle's illustration.
+    fn highlight(&self, words: &[Found], length: usize) -> String {
+        let mut grid: Vec<Vec<char>> = self.0.iter().map(|row| vec!['.'; row.len()]).collect();
+        for ((x, y), (dx, dy)) in words {
+            for i in 0..length as isize {
+                let (x, y) = (x.wrapping_add_signed(dx * i), y.wrapping_add_signed(dy * i));
+                grid[y][x] = self.0[y][x] as char;
+            }
+        }
+        grid.iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
 
-impl From<&str> for LetterWall {
-    fn from(value: &str) -> Self {
-        Self(value.lines().map(|v| v.chars().collect()).collect())
+impl<'a> From<&'a str> for LetterWall<'a> {
+    fn from(value: &'a str) -> Self {
+        Self(value.lines().map(|v| v.as_bytes()).collect())
     }
 }
 
-type Data = LetterWall;
+type Data<'a> = LetterWall<'a>;
 
-impl DayImpl<Data> for Day<CURRENT_DAY> {
-    fn init_test() -> (Self, Data) {
-        Self::init(include_str!("test_inputs/test04.txt"))
-    }
+impl<'a> DayImpl<'a, Data<'a>> for Day<CURRENT_DAY> {
+    const MUTATES_DATA: bool = false;
+
+    const EXAMPLE: &'static str = include_str!("test_inputs/test04.txt");
 
     fn expected_results() -> (Answer, Answer) {
         (Answer::Number(18), Answer::Number(9))
     }
 
-    fn init(input: &str) -> (Self, Data) {
+    fn init(input: &'a str) -> (Self, Data<'a>) {
         (Self {}, input.into())
     }
 
-    fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.count_xmas())
+    fn one(&self, data: &mut Data<'a>, _: &()) -> Result<Answer, SolveError> {
+        Ok(Answer::Number(data.count_xmas()))
+    }
+
+    fn two(&self, data: &mut Data<'a>, _: &()) -> Result<Answer, SolveError> {
+        Ok(Answer::Number(data.count_cross_mas()))
     }
 
-    fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.count_cross_mas())
+    fn extra_parts() -> Vec<ExtraPart<Self, Data<'a>, ()>> {
+        vec![ExtraPart {
+            name: "xmasamx",
+            solve: |_, data, _| Ok(data.find_word(b"XMASAMX").len().into()),
+            example: None,
+            expected: Answer::Number(3),
+        }]
     }
+
+    fn tools() -> Vec<Tool<Self, Data<'a>>> {
+        vec![Tool {
+            command: || {
+                SubCommand::with_name("find")
+                    .about("Find a word in the letter wall, in any of the 8 directions.")
+                    .arg(
+                        Arg::with_name("word")
+                            .help("The word to find, case insensitive.")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::with_name("grid")
+                            .help("Draw the wall with only the letters of the found words.")
+                            .long("grid"),
+                    )
+            },
+            run: |_, data, args| {
+                let word = args.value_of("word").unwrap().to_uppercase();
+                if word.is_empty() {
+                    return Err("The word can't be empty.".to_string());
+                }
+                let found = data.find_word(word.as_bytes());
+
+                let mut out = format!("Found {} {} times.", word, found.len());
+                if args.is_present("grid") {
+                    out += "\n";
+                    out += &data.highlight(&found, word.len());
+                } else {
+                    for ((x, y), direction) in &found {
+                        let arrow = match direction {
+                            (1, 0) => '→',
+                            (1, 1) => '↘',
+                            (0, 1) => '↓',
+                            (-1, 1) => '↙',
+                            (-1, 0) => '←',
+                            (-1, -1) => '↖',
+                            (0, -1) => '↑',
+                            _ => '↗',
+                        };
+                        out += &format!("\n\tx {:3}, y {:3}  {}", x, y, arrow);
+                    }
+                }
+                Ok(out)
+            },
+        }]
+    }
+}
+
+impl Metamorphic for Day<CURRENT_DAY> {
+    const TRANSFORMS: &'static [Transform] = &[
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+    ];
 }
@@ -1,6 +1,8 @@
 use std::collections::BTreeSet;
 
-use super::{Answer, Day, DayImpl};
+use super::{Answer, Day, DayImpl, InputGenerator, SolveError, Tool};
+use crate::fuzz::Rng;
+use clap::{Arg, SubCommand};
 
 const CURRENT_DAY: u8 = 5;
 
@@ -27,8 +29,8 @@ impl From<&str> for PageOrderingRule {
 pub struct UpdateSequence(Vec<u64>);
 
 impl UpdateSequence {
-    fn matches_rules(&self, rules: &Vec<PageOrderingRule>) -> bool {
-        let contained: BTreeSet<u64> = self.0.iter().map(|v| *v).collect();
+    fn matches_rules(&self, rules: &[PageOrderingRule]) -> bool {
+        let contained: BTreeSet<u64> = self.0.iter().copied().collect();
         let mut seen = BTreeSet::new();
 
         for page_number in &self.0 {
@@ -50,13 +52,27 @@ impl UpdateSequence {
         true
     }
 
+    /// The rules with both pages in the update, but in the wrong order.
+    fn broken_rules<'r>(&self, rules: &'r [PageOrderingRule]) -> Vec<&'r PageOrderingRule> {
+        let position = |page: u64| self.0.iter().position(|v| *v == page);
+        rules
+            .iter()
+            .filter(|v| {
+                matches!(
+                    (position(v.first), position(v.second)),
+                    (Some(first), Some(second)) if first > second
+                )
+            })
+            .collect()
+    }
+
     fn get_middle_page_number(&self) -> u64 {
         self.0[self.0.len() / 2]
     }
 
-    fn get_rule_conforming(&self, rules: &Vec<PageOrderingRule>) -> Self {
+    fn get_rule_conforming(&self, rules: &[PageOrderingRule]) -> Self {
         let mut sorted: Vec<u64> = Vec::with_capacity(self.0.len());
-        let contained: BTreeSet<u64> = self.0.iter().map(|v| *v).collect();
+        let contained: BTreeSet<u64> = self.0.iter().copied().collect();
 
         let mut numbers_with_edges = self
             .0
@@ -74,7 +90,7 @@ impl UpdateSequence {
             })
             .collect::<Vec<(u64, Vec<u64>)>>();
 
-        numbers_with_edges.sort_by(|a, b| a.1.len().cmp(&b.1.len()));
+        numbers_with_edges.sort_by_key(|a| a.1.len());
         for (number, edges) in numbers_with_edges {
             for edge in edges {
                 if !sorted.contains(&edge) {
@@ -115,10 +131,10 @@ impl From<&str> for SafetyManualPrintOrder {
 }
 
 type Data = SafetyManualPrintOrder;
-impl DayImpl<Data> for Day<CURRENT_DAY> {
-    fn init_test() -> (Self, Data) {
-        Self::init(include_str!("test_inputs/test05.txt"))
-    }
+impl DayImpl<'_, Data> for Day<CURRENT_DAY> {
+    const MUTATES_DATA: bool = false;
+
+    const EXAMPLE: &'static str = include_str!("test_inputs/test05.txt");
 
     fn expected_results() -> (Answer, Answer) {
         (Answer::Number(143), Answer::Number(123))
@@ -128,24 +144,124 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
         (Self {}, input.into())
     }
 
-    fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(
+    fn one(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
+        Ok(Answer::Number(
             data.updates
                 .iter()
                 .filter(|v| v.matches_rules(&data.rules))
                 .map(|v| v.get_middle_page_number())
                 .sum(),
-        )
+        ))
     }
 
-    fn two(&self, data: &mut Data) -> Answer {
+    fn two(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
         data.updates[0].get_rule_conforming(&data.rules);
-        Answer::Number(
+        Ok(Answer::Number(
             data.updates
                 .iter()
                 .filter(|v| !v.matches_rules(&data.rules))
                 .map(|v| v.get_rule_conforming(&data.rules).get_middle_page_number())
                 .sum(),
-        )
+        ))
+    }
+
+    fn tools() -> Vec<Tool<Self, Data>> {
+        vec![Tool {
+            command: || {
+                SubCommand::with_name("check-update")
+                    .about("Check an update against the rules of the input, showing the rules it breaks and the order that follows them.")
+                    .arg(
+                        Arg::with_name("pages")
+                            .help("The pages of the update, separated by commas, like 75,47,61.")
+                            .required(true),
+                    )
+            },
+            run: |_, data, args| {
+                let pages = args.value_of("pages").unwrap();
+                let update = UpdateSequence(
+                    pages
+                        .split(',')
+                        .map(|v| {
+                            v.trim()
+                                .parse()
+                                .map_err(|_| format!("\"{}\" is not a page number.", v))
+                        })
+                        .collect::<Result<_, _>>()?,
+                );
+
+                let broken = update.broken_rules(&data.rules);
+                if broken.is_empty() {
+                    return Ok(format!(
+                        "The update follows the rules, its middle page is {}.",
+                        update.get_middle_page_number()
+                    ));
+                }
+
+                let fixed = update.get_rule_conforming(&data.rules);
+                let mut out = format!(
+                    "The update breaks {} {}:\n",
+                    broken.len(),
+                    if broken.len() == 1 { "rule" } else { "rules" }
+                );
+                for rule in broken {
+                    out += &format!("\t{}|{}\n", rule.first, rule.second);
+                }
+                out += &format!(
+                    "Following them, it is {} with the middle page {}.",
+                    fixed
+                        .0
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    fixed.get_middle_page_number()
+                );
+                Ok(out)
+            },
+        }]
+    }
+}
+
+impl InputGenerator for Day<CURRENT_DAY> {
+    /// Generates `size` rules and `size` updates over at most 90 two-digit pages.
+    /// The rules follow a random order of the pages, so the updates can always be fixed.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let size = size.max(1);
+        let mut order: Vec<u64> = (10..100).collect();
+        for i in (1..order.len()).rev() {
+            order.swap(i, rng.below(i as u64 + 1) as usize);
+        }
+        order.truncate((size + 1).min(order.len()));
+
+        let rules = (0..size).map(|_| {
+            let first = rng.below(order.len() as u64 - 1) as usize;
+            let second = rng.range(first as u64 + 1, order.len() as u64 - 1) as usize;
+            format!("{}|{}", order[first], order[second])
+        });
+        let rules = rules.collect::<Vec<_>>().join("\n");
+
+        let updates = (0..size)
+            .map(|_| {
+                let mut pages = order.clone();
+                for i in (1..pages.len()).rev() {
+                    pages.swap(i, rng.below(i as u64 + 1) as usize);
+                }
+                // Updates have an odd length, so that they have a middle page.
+                let max_length = pages.len().min(23) as u64;
+                pages.truncate(2 * rng.below(max_length.div_ceil(2)) as usize + 1);
+                // About half of the updates are already in the right order, like in real inputs.
+                if rng.chance(0.5) {
+                    pages.sort_by_key(|v| order.iter().position(|o| o == v));
+                }
+                pages
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!("{}\n\n{}", rules, updates)
     }
 }
@@ -1,4 +1,10 @@
-use super::{Answer, Day, DayImpl};
+use super::{
+    Answer, Day, DayImpl, InputGenerator, Metamorphic, Parameters, SolveError, utils::Map,
+};
+use crate::fuzz::Rng;
+use crate::metamorphic::Transform;
+use crate::visualizer::Frame;
+use crate::{checkpoint, debug_step, visualize, vprintln};
 use std::{collections::HashSet, hash::Hash};
 
 const CURRENT_DAY: u8 = 6;
@@ -24,7 +30,7 @@ impl Direction {
 
 /**
 A 2D position
-```
+```text
    y-
 x- + x+
    y+
@@ -32,17 +38,17 @@ x- + x+
 */
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Position {
-    x: u8,
-    y: u8,
+    x: usize,
+    y: usize,
 }
 
 impl Position {
     fn move_in_direction(
         &self,
         dir: Direction,
-        steps: u8,
-        limit_x: u8,
-        limit_y: u8,
+        steps: usize,
+        limit_x: usize,
+        limit_y: usize,
     ) -> Option<Self> {
         match dir {
             Direction::North => Some(Self {
@@ -59,20 +65,20 @@ impl Position {
                     })
                 }
             }
-            Direction::East => Some(Self {
-                x: self.x.checked_add(steps)?,
-                y: self.y,
-            }),
-            Direction::West => {
+            Direction::East => {
                 if self.x + 1 >= limit_x {
                     None
                 } else {
                     Some(Self {
-                        x: self.x.checked_sub(steps)?,
+                        x: self.x.checked_add(steps)?,
                         y: self.y,
                     })
                 }
             }
+            Direction::West => Some(Self {
+                x: self.x.checked_sub(steps)?,
+                y: self.y,
+            }),
         }
     }
 }
@@ -85,9 +91,18 @@ pub struct PatrolPathIterator<'a> {
 
 impl<'a> PatrolPathIterator<'a> {
     fn is_loop(&mut self) -> bool {
-        let mut visited = HashSet::new();
+        let mut visited: HashSet<(Position, Direction)> = HashSet::new();
+        let map = self.map;
 
         for current in self {
+            debug_step!(
+                current,
+                frame: Frame {
+                    grid: map.to_grid(&visited.iter().map(|v| v.0).collect(), Some(current)),
+                    highlights: vec![(current.0.x, current.0.y)],
+                },
+                break "revisit" if visited.contains(&current)
+            );
             if visited.contains(&current) {
                 return true;
             }
@@ -102,30 +117,35 @@ impl<'a> Iterator for PatrolPathIterator<'a> {
     type Item = (Position, Direction);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut next_pos =
-            self.position?
-                .move_in_direction(self.direction, 1, self.map.width, self.map.height);
-
-        if self.map.is_obstacle(next_pos?) {
-            self.direction = self.direction.turn_right();
-            next_pos = self.position?.move_in_direction(
-                self.direction,
-                1,
-                self.map.width,
-                self.map.height,
-            );
+        let position = self.position?;
+
+        // The guard may have to turn more than once, for example in a dead end.
+        for _ in 0..4 {
+            let next_pos =
+                position.move_in_direction(self.direction, 1, self.map.width, self.map.height);
+            match next_pos {
+                Some(pos) if self.map.is_obstacle(pos) => {
+                    self.direction = self.direction.turn_right();
+                }
+                _ => {
+                    self.position = next_pos;
+                    return Some((self.position?, self.direction));
+                }
+            }
         }
-        self.position = next_pos;
-        Some((self.position?, self.direction))
+
+        // Boxed in on all sides, the guard stays where it is, which is a loop.
+        Some((position, self.direction))
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct PatrollingMap {
-    pub width: u8,
-    pub height: u8,
+    pub width: usize,
+    pub height: usize,
     obstacles: HashSet<Position>,
     start_position: Position,
+    start_direction: Direction,
 }
 
 impl PatrollingMap {
@@ -137,10 +157,40 @@ impl PatrollingMap {
         self.obstacles.insert(pos);
     }
 
-    pub fn iter(&self) -> PatrolPathIterator {
+    /// Draws the map, with the visited positions marked and the guard if provided.
+    pub fn to_grid(
+        &self,
+        visited: &HashSet<Position>,
+        guard: Option<(Position, Direction)>,
+    ) -> Vec<Vec<char>> {
+        let mut grid = vec![vec!['.'; self.width]; self.height];
+        // Positions outside of the map are skipped, as the path may leave it.
+        let mut draw = |pos: &Position, c: char| {
+            if let Some(tile) = grid.get_mut(pos.y).and_then(|row| row.get_mut(pos.x)) {
+                *tile = c;
+            }
+        };
+
+        visited.iter().for_each(|pos| draw(pos, 'X'));
+        self.obstacles.iter().for_each(|pos| draw(pos, '#'));
+
+        let (pos, dir) = guard.unwrap_or((self.start_position, self.start_direction));
+        draw(
+            &pos,
+            match dir {
+                Direction::North => '^',
+                Direction::East => '>',
+                Direction::South => 'v',
+                Direction::West => '<',
+            },
+        );
+        grid
+    }
+
+    pub fn iter(&self) -> PatrolPathIterator<'_> {
         PatrolPathIterator {
             position: Some(self.start_position),
-            direction: Direction::North,
+            direction: self.start_direction,
             map: self,
         }
     }
@@ -148,24 +198,26 @@ impl PatrollingMap {
 
 impl From<&str> for PatrollingMap {
     fn from(value: &str) -> Self {
-        let mut start_position: Option<Position> = None;
+        let mut start: Option<(Position, Direction)> = None;
 
         let lines = value.lines();
 
-        let height = lines.clone().count() as u8;
-        let width = lines.clone().next().unwrap().len() as u8;
+        let height = lines.clone().count();
+        let width = lines.clone().next().unwrap().len();
 
         let obstacles = lines
             .map(|line| line.chars().enumerate().collect::<Vec<(usize, char)>>())
             .enumerate()
             .inspect(|(y, chars)| {
                 chars.iter().for_each(|(x, c)| {
-                    if *c == '^' {
-                        start_position = Some(Position {
-                            x: *x as u8,
-                            y: *y as u8,
-                        });
-                    }
+                    let direction = match c {
+                        '^' => Direction::North,
+                        '>' => Direction::East,
+                        'v' => Direction::South,
+                        '<' => Direction::West,
+                        _ => return,
+                    };
+                    start = Some((Position { x: *x, y: *y }, direction));
                 });
             })
             .map(|(y, chars)| {
@@ -179,19 +231,18 @@ impl From<&str> for PatrollingMap {
                 )
             })
             .fold(vec![], |mut acc, (y, obstacles_in_row)| {
-                obstacles_in_row.iter().for_each(|x| {
-                    acc.push(Position {
-                        x: *x as u8,
-                        y: y as u8,
-                    })
-                });
+                obstacles_in_row
+                    .iter()
+                    .for_each(|x| acc.push(Position { x: *x, y }));
                 acc
             })
             .into_iter()
             .collect();
 
+        let (start_position, start_direction) = start.expect("Couldn't find start position");
         Self {
-            start_position: start_position.expect("Couldn't find start position"),
+            start_position,
+            start_direction,
             obstacles,
             width,
             height,
@@ -199,46 +250,222 @@ impl From<&str> for PatrollingMap {
     }
 }
 
-type Data = PatrollingMap;
-impl DayImpl<Data> for Day<CURRENT_DAY> {
-    fn init_test() -> (Self, Data) {
-        Self::init(include_str!("test_inputs/test06.txt"))
+/// The size of the lab, or `None` for the size of the input, which both the example
+/// and the real input are. Set with `--param width=...` or `--param height=...`.
+///
+/// An input smaller than the lab is extended with empty tiles to the right and bottom,
+/// a larger one keeps its size, so the guard always starts inside the lab.
+#[derive(Debug, Clone, Copy)]
+pub struct LabSize {
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+}
+
+impl LabSize {
+    /// Larger labs would take too long to draw and patrol.
+    const MAX: usize = 1000;
+}
+
+impl Parameters for LabSize {
+    const KEYS: &'static [&'static str] = &["width", "height"];
+
+    fn example() -> Self {
+        Self::real()
     }
 
+    fn real() -> Self {
+        Self {
+            width: None,
+            height: None,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let size = value
+            .parse::<usize>()
+            .ok()
+            .filter(|v| (1..=Self::MAX).contains(v))
+            .ok_or_else(|| format!("the lab must be 1 to {} tiles large", Self::MAX))?;
+        match key {
+            "width" => self.width = Some(size),
+            "height" => self.height = Some(size),
+            _ => unreachable!("keys are checked against KEYS"),
+        }
+        Ok(())
+    }
+}
+
+type Data = PatrollingMap;
+type Shared = HashSet<Position>;
+impl DayImpl<'_, Data, Shared> for Day<CURRENT_DAY> {
+    const MUTATES_DATA: bool = false;
+
+    const EXAMPLE: &'static str = include_str!("test_inputs/test06.txt");
+
     fn expected_results() -> (Answer, Answer) {
         (Answer::Number(41), Answer::Number(6))
     }
 
     fn init(input: &str) -> (Self, Data) {
-        (Self {}, input.into())
+        let mut map: PatrollingMap = input.into();
+        let size = LabSize::current();
+        map.width = size.width.map_or(map.width, |v| v.max(map.width));
+        map.height = size.height.map_or(map.height, |v| v.max(map.height));
+        (Self {}, map)
     }
 
-    fn one(&self, data: &mut Data) -> Answer {
-        let visited: HashSet<Position> = data
-            .iter()
-            //.inspect(|v| println!("{:?}", v))
-            .map(|v| v.0)
-            .collect();
-        Answer::Number(visited.len() as u64)
+    /// Collects every position on the guard's unobstructed patrol path.
+    fn precompute(&self, data: &Data) -> Shared {
+        let mut visited = HashSet::new();
+        for (pos, dir) in data.iter() {
+            visited.insert(pos);
+            debug_step!(
+                (pos, dir),
+                frame: Frame {
+                    grid: data.to_grid(&visited, Some((pos, dir))),
+                    highlights: vec![(pos.x, pos.y)],
+                }
+            );
+            visualize!(
+                data.to_grid(&visited, Some((pos, dir))),
+                vec![(pos.x, pos.y)]
+            );
+        }
+        vprintln!(
+            "guard left the map after visiting {} positions",
+            visited.len()
+        );
+        visited
     }
 
-    fn two(&self, data: &mut Data) -> Answer {
-        let main_path_visited: HashSet<Position> = data
-            .iter()
-            //.inspect(|v| println!("{:?}", v))
-            .map(|v| v.0)
-            .collect();
+    /// Draws the obstacles and the path of the guard.
+    fn exposed_map(&self, data: &Data, main_path_visited: &Shared) -> Option<Map<char>> {
+        Some(data.to_grid(main_path_visited, None).into())
+    }
+
+    fn one(&self, _: &mut Data, main_path_visited: &Shared) -> Result<Answer, SolveError> {
+        Ok(main_path_visited.len().into())
+    }
 
+    fn two(&self, data: &mut Data, main_path_visited: &Shared) -> Result<Answer, SolveError> {
         // NOTE: More than 1812
-        Answer::Number(
-            main_path_visited
-                .iter()
-                .filter(|pos| {
-                    let mut map = data.clone();
-                    map.add_obstruction(**pos);
-                    map.iter().is_loop()
+        Ok(main_path_visited
+            .iter()
+            .filter(|pos| {
+                let mut map = data.clone();
+                map.add_obstruction(**pos);
+                checkpoint!("place obstruction");
+                let is_loop = map.iter().is_loop();
+                checkpoint!("loop check");
+                is_loop
+            })
+            .count()
+            .into())
+    }
+}
+
+impl Metamorphic for Day<CURRENT_DAY> {
+    /// Mirroring the map would make the guard turn left instead of right.
+    const TRANSFORMS: &'static [Transform] = &[
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+    ];
+    const ARROWS: bool = true;
+}
+
+impl InputGenerator for Day<CURRENT_DAY> {
+    /// Generates maps of up to `size` by `size` tiles, from which the guard walks off the map.
+    /// The guard often starts on an edge or facing an obstacle.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let size = size.clamp(1, 200) as u64;
+        loop {
+            let width = rng.range(1, size) as usize;
+            let height = rng.range(1, size) as usize;
+            let density = rng.range(0, 30) as f64 / 100.0;
+            let mut grid: Vec<Vec<char>> = (0..height)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| if rng.chance(density) { '#' } else { '.' })
+                        .collect()
                 })
-                .count() as u64,
-        )
+                .collect();
+
+            let x = rng.below(width as u64) as usize;
+            let y = match rng.below(4) {
+                0 => 0,
+                1 => height - 1,
+                _ => rng.below(height as u64) as usize,
+            };
+            grid[y][x] = '^';
+            if y > 0 && rng.chance(0.3) {
+                grid[y - 1][x] = '#';
+            }
+
+            if guard_leaves(&grid, x, y) {
+                return grid
+                    .iter()
+                    .map(|row| row.iter().collect::<String>())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+        }
     }
+
+    /// Removes rows and columns that don't contain the guard.
+    fn shrink(input: &str) -> Vec<String> {
+        let grid: Vec<Vec<char>> = input.lines().map(|v| v.chars().collect()).collect();
+        let join = |grid: Vec<Vec<char>>| {
+            grid.iter()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let mut candidates = vec![];
+
+        for y in 0..grid.len() {
+            if grid.len() > 1 && !grid[y].contains(&'^') {
+                let mut smaller = grid.clone();
+                smaller.remove(y);
+                candidates.push(join(smaller));
+            }
+        }
+        for x in 0..grid[0].len() {
+            if grid[0].len() > 1 && grid.iter().all(|row| row.get(x) != Some(&'^')) {
+                let smaller = grid
+                    .iter()
+                    .map(|row| {
+                        let mut row = row.clone();
+                        row.remove(x);
+                        row
+                    })
+                    .collect();
+                candidates.push(join(smaller));
+            }
+        }
+
+        candidates
+    }
+}
+
+/// Walks the guard by the puzzle's rules, returning whether it leaves the map instead of looping.
+fn guard_leaves(grid: &[Vec<char>], x: usize, y: usize) -> bool {
+    let (width, height) = (grid[0].len() as i64, grid.len() as i64);
+    let (mut x, mut y) = (x as i64, y as i64);
+    let (mut dx, mut dy) = (0, -1);
+    let mut visited = HashSet::new();
+
+    while visited.insert((x, y, dx, dy)) {
+        let (nx, ny) = (x + dx, y + dy);
+        if nx < 0 || ny < 0 || nx >= width || ny >= height {
+            return true;
+        }
+        if grid[ny as usize][nx as usize] == '#' {
+            (dx, dy) = (-dy, dx);
+        } else {
+            (x, y) = (nx, ny);
+        }
+    }
+
+    false
 }
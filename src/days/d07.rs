@@ -1,4 +1,6 @@
-use super::{Answer, Day, DayImpl};
+use super::{Answer, Day, DayImpl, ExtraPart, InputGenerator, Metamorphic, PartFn, SolveError};
+use crate::fuzz::Rng;
+use crate::metamorphic::Transform;
 
 const CURRENT_DAY: u8 = 7;
 
@@ -7,6 +9,9 @@ enum Operator {
     Add,
     Multiply,
     Concat,
+    /// Not part of the puzzle. Like the others it is evaluated left to right,
+    /// an equation that goes below zero can't be valid.
+    Subtract,
 }
 
 impl Operator {
@@ -23,12 +28,20 @@ impl Operator {
         count
     }
 
+    /// The power of ten concatenating `n` shifts by, `None` if it doesn't fit.
     #[inline(always)]
-    fn calculate(&self, a: u64, b: u64) -> u64 {
+    fn concat_shift(n: u64) -> Option<u64> {
+        10_u64.checked_pow(Self::num_digits(n))
+    }
+
+    /// `None` if the result doesn't fit, which can't match the result of an equation either.
+    #[inline(always)]
+    fn calculate(&self, a: u64, b: u64) -> Option<u64> {
         match self {
-            Operator::Multiply => a * b,
-            Operator::Add => a + b,
-            Operator::Concat => a * 10_u64.pow(Self::num_digits(b)) + b,
+            Operator::Multiply => a.checked_mul(b),
+            Operator::Add => a.checked_add(b),
+            Operator::Concat => Self::concat_shift(b)?.checked_mul(a)?.checked_add(b),
+            Operator::Subtract => a.checked_sub(b),
         }
     }
 }
@@ -79,7 +92,7 @@ where
             .map(|p| self.possibilities[self.get_index_on_position(p)].clone())
             .collect();
         self.iteration += 1;
-        return Some(values);
+        Some(values)
     }
 }
 
@@ -90,21 +103,50 @@ pub struct CalibrationEquation {
 }
 
 impl CalibrationEquation {
-    fn calculate(&self, operations: Vec<Operator>) -> u64 {
+    fn calculate(&self, operations: Vec<Operator>) -> Option<u64> {
         let mut value = self.parts[0];
 
         for i in 1..(self.parts.len()) {
-            value = operations[i - 1].calculate(value, self.parts[i]);
+            value = operations[i - 1].calculate(value, self.parts[i])?;
         }
 
-        value
+        Some(value)
     }
 
     fn can_be_valid(&self, operations: &[Operator]) -> bool {
         CombinationIterator::new(operations, (self.parts.len() - 1) as u32)
             .map(|o| self.calculate(o))
             .collect::<Vec<_>>()
-            .contains(&self.result)
+            .contains(&Some(self.result))
+    }
+
+    /// Works backwards from the result, undoing the last operation,
+    /// which rules out most combinations of operators early.
+    fn can_be_valid_pruned(&self, operations: &[Operator]) -> bool {
+        Self::is_reachable(self.result, &self.parts, operations)
+    }
+
+    fn is_reachable(target: u64, parts: &[u64], operations: &[Operator]) -> bool {
+        let Some((last, rest)) = parts.split_last() else {
+            return false;
+        };
+        if rest.is_empty() {
+            return target == *last;
+        }
+
+        operations.iter().any(|operation| match operation {
+            Operator::Add => target >= *last && Self::is_reachable(target - last, rest, operations),
+            Operator::Multiply if *last == 0 => target == 0,
+            Operator::Multiply => {
+                target.is_multiple_of(*last) && Self::is_reachable(target / last, rest, operations)
+            }
+            Operator::Concat => Operator::concat_shift(*last).is_some_and(|shift| {
+                target % shift == *last && Self::is_reachable(target / shift, rest, operations)
+            }),
+            Operator::Subtract => target
+                .checked_add(*last)
+                .is_some_and(|v| Self::is_reachable(v, rest, operations)),
+        })
     }
 }
 
@@ -115,7 +157,6 @@ impl From<&str> for CalibrationEquation {
         Self {
             result: first.parse().unwrap(),
             parts: second
-                .trim()
                 .split_whitespace()
                 .map(|v| v.parse().unwrap())
                 .collect(),
@@ -136,6 +177,14 @@ impl Calibrator {
             .map(|v| v.result)
             .sum()
     }
+
+    fn get_valid_sum_pruned(&self, operators: &[Operator]) -> u64 {
+        self.equations
+            .iter()
+            .filter(|v| v.can_be_valid_pruned(operators))
+            .map(|v| v.result)
+            .sum()
+    }
 }
 
 impl From<&str> for Calibrator {
@@ -147,10 +196,10 @@ impl From<&str> for Calibrator {
 }
 
 type Data = Calibrator;
-impl DayImpl<Data> for Day<CURRENT_DAY> {
-    fn init_test() -> (Self, Data) {
-        Self::init(include_str!("test_inputs/test07.txt"))
-    }
+impl DayImpl<'_, Data> for Day<CURRENT_DAY> {
+    const MUTATES_DATA: bool = false;
+
+    const EXAMPLE: &'static str = include_str!("test_inputs/test07.txt");
 
     fn expected_results() -> (Answer, Answer) {
         (Answer::Number(3749), Answer::Number(11387))
@@ -160,11 +209,93 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
         (Self {}, input.into())
     }
 
-    fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.get_valid_sum(&[Operator::Add, Operator::Multiply]))
+    fn one(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
+        Ok(Answer::Number(
+            data.get_valid_sum(&[Operator::Add, Operator::Multiply]),
+        ))
+    }
+
+    fn two(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
+        Ok(Answer::Number(data.get_valid_sum(&[
+            Operator::Add,
+            Operator::Multiply,
+            Operator::Concat,
+        ])))
+    }
+
+    fn one_variants() -> Vec<(&'static str, PartFn<Self, Data, ()>)> {
+        vec![("pruned", |_, data, _| {
+            Ok(Answer::Number(data.get_valid_sum_pruned(&[
+                Operator::Add,
+                Operator::Multiply,
+            ])))
+        })]
+    }
+
+    fn two_variants() -> Vec<(&'static str, PartFn<Self, Data, ()>)> {
+        vec![("pruned", |_, data, _| {
+            Ok(Answer::Number(data.get_valid_sum_pruned(&[
+                Operator::Add,
+                Operator::Multiply,
+                Operator::Concat,
+            ])))
+        })]
     }
 
-    fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.get_valid_sum(&[Operator::Add, Operator::Multiply, Operator::Concat]))
+    fn extra_parts() -> Vec<ExtraPart<Self, Data, ()>> {
+        vec![ExtraPart {
+            name: "subtract",
+            solve: |_, data, _| {
+                Ok(Answer::Number(data.get_valid_sum_pruned(&[
+                    Operator::Add,
+                    Operator::Multiply,
+                    Operator::Concat,
+                    Operator::Subtract,
+                ])))
+            },
+            // Subtraction doesn't help any equation of the puzzle's example.
+            example: Some(include_str!("test_inputs/test07_subtract.txt")),
+            expected: Answer::Number(365),
+        }]
+    }
+}
+
+impl Metamorphic for Day<CURRENT_DAY> {
+    const TRANSFORMS: &'static [Transform] = &[Transform::ShuffleLines];
+}
+
+impl InputGenerator for Day<CURRENT_DAY> {
+    /// Half of the equations can be solved, using random operators.
+    /// `size` is the number of equations, with up to 12 numbers each.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let parts: Vec<u64> = (0..rng.range(1, 12)).map(|_| rng.range(1, 999)).collect();
+
+                let mut result = Some(parts[0]);
+                for part in &parts[1..] {
+                    result = result.and_then(|v| match rng.below(3) {
+                        0 => v.checked_add(*part),
+                        1 => v.checked_mul(*part),
+                        _ => format!("{}{}", v, part).parse().ok(),
+                    });
+                }
+                let result = match result {
+                    Some(result) if rng.chance(0.5) => result,
+                    _ => rng.range(1, 1_000_000_000_000),
+                };
+
+                format!(
+                    "{}: {}",
+                    result,
+                    parts
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
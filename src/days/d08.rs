@@ -3,7 +3,8 @@ use std::{
     ops::{Add, Mul, Sub},
 };
 
-use super::{Answer, Day, DayImpl};
+use super::{Answer, Day, DayImpl, Metamorphic, SolveError};
+use crate::metamorphic::Transform;
 
 const CURRENT_DAY: u8 = 8;
 
@@ -153,7 +154,7 @@ impl From<&str> for AntennaMap {
                             .collect::<Vec<_>>(),
                     )
                 })
-                .filter(|(_, line)| line.len() > 0)
+                .filter(|(_, line)| !line.is_empty())
                 .fold(HashMap::new(), |mut map, (y, line)| {
                     line.into_iter().for_each(|(x, frequency)| {
                         if let Some(antenna) = map.get_mut(&frequency) {
@@ -170,16 +171,16 @@ impl From<&str> for AntennaMap {
                     });
                     map
                 }),
-            limits: limits,
+            limits,
         }
     }
 }
 
 type Data = AntennaMap;
-impl DayImpl<Data> for Day<CURRENT_DAY> {
-    fn init_test() -> (Self, Data) {
-        Self::init(include_str!("test_inputs/test08.txt"))
-    }
+impl DayImpl<'_, Data> for Day<CURRENT_DAY> {
+    const MUTATES_DATA: bool = false;
+
+    const EXAMPLE: &'static str = include_str!("test_inputs/test08.txt");
 
     fn expected_results() -> (Answer, Answer) {
         (Answer::Number(14), Answer::Number(34))
@@ -189,21 +190,32 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
         (Self {}, input.into())
     }
 
-    fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(
-            data.get_all_antinodes()
-                .into_iter()
-                .collect::<HashSet<Vector>>()
-                .len() as u64,
-        )
+    fn one(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
+        Ok(data
+            .get_all_antinodes()
+            .into_iter()
+            .collect::<HashSet<Vector>>()
+            .len()
+            .into())
     }
 
-    fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(
-            data.get_all_resonances()
-                .into_iter()
-                .collect::<HashSet<Vector>>()
-                .len() as u64,
-        )
+    fn two(&self, data: &mut Data, _: &()) -> Result<Answer, SolveError> {
+        Ok(data
+            .get_all_resonances()
+            .into_iter()
+            .collect::<HashSet<Vector>>()
+            .len()
+            .into())
     }
 }
+
+impl Metamorphic for Day<CURRENT_DAY> {
+    const TRANSFORMS: &'static [Transform] = &[
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+    ];
+}
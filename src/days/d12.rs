@@ -1,12 +1,10 @@
-use super::{Answer, Day, DayImpl};
+use super::{Answer, Day, DayImpl, SolveError};
 
 const CURRENT_DAY: u8 = 12;
 
 type Data = Vec<u64>;
-impl DayImpl<Data> for Day<CURRENT_DAY> {
-    fn init_test() -> (Self, Data) {
-        Self::init(include_str!("test_inputs/test12.txt"))
-    }
+impl DayImpl<'_, Data> for Day<CURRENT_DAY> {
+    const EXAMPLE: &'static str = include_str!("test_inputs/test12.txt");
 
     fn expected_results() -> (Answer, Answer) {
         (Answer::Number(0), Answer::Number(0))
@@ -22,11 +20,11 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
         )
     }
 
-    fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.len() as u64)
+    fn one(&self, _: &mut Data, _: &()) -> Result<Answer, SolveError> {
+        Err(SolveError::Unimplemented)
     }
 
-    fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.len() as u64)
+    fn two(&self, _: &mut Data, _: &()) -> Result<Answer, SolveError> {
+        Err(SolveError::Unimplemented)
     }
 }
@@ -1,6 +1,16 @@
+use crate::checkpoints;
+use crate::fuzz::{Rng, shrink_lines};
+use crate::input;
+use crate::metamorphic::Transform;
+use crate::params::{self, Source};
+use crate::variants;
 use aoc_macro::mod_days;
+use clap::{App, ArgMatches};
+use std::panic::{self, AssertUnwindSafe};
 use std::time::{Duration, Instant};
+use tracing::info_span;
 
+pub mod ocr;
 pub mod utils;
 
 // Thanks to andi-makes with his AoC project https://github.com/andi-makes/aoc2021,
@@ -9,11 +19,16 @@ pub mod utils;
 pub struct Day<const DAY: u8>;
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum Answer {
     Number(u64),
+    Signed(i64),
+    Big(u128),
     String(String),
+    /// Multiple values, submitted separated by commas.
+    List(Vec<Answer>),
     Bitmap(Vec<Vec<bool>>),
+    Grid(Vec<Vec<char>>),
 }
 
 impl Answer {
@@ -36,7 +51,7 @@ impl Answer {
         false
     }
 
-    fn minify_bitmap(bm: &Vec<Vec<bool>>) -> String {
+    fn minify_bitmap(bm: &[Vec<bool>]) -> String {
         let height = bm.len();
         let width = bm[0].len();
 
@@ -77,177 +92,940 @@ impl Answer {
 
         Self::append_per_line(out, "\t\t")
     }
+
+    fn as_integer(&self) -> Option<i128> {
+        match self {
+            Self::Number(n) => Some(*n as i128),
+            Self::Signed(n) => Some(*n as i128),
+            Self::Big(n) => i128::try_from(*n).ok(),
+            _ => None,
+        }
+    }
+
+    /// The answer in the form it would be submitted to AoC.
+    ///
+    /// Returns None for bitmaps that don't only contain known letters.
+    pub fn as_submission(&self) -> Option<String> {
+        match self {
+            Self::Number(n) => Some(n.to_string()),
+            Self::Signed(n) => Some(n.to_string()),
+            Self::Big(n) => Some(n.to_string()),
+            Self::String(s) => Some(s.clone()),
+            Self::List(l) => l
+                .iter()
+                .map(|v| v.as_submission())
+                .collect::<Option<Vec<String>>>()
+                .map(|v| v.join(",")),
+            Self::Bitmap(bm) => ocr::recognize(bm),
+            Self::Grid(g) => Some(
+                g.iter()
+                    .map(|line| line.iter().collect::<String>())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            ),
+        }
+    }
+
+    /// The submission, or for bitmaps that couldn't be read, the bitmap drawn with `#` and `.`.
+    pub fn as_text(&self) -> String {
+        self.as_submission().unwrap_or_else(|| match self {
+            Self::Bitmap(bm) => bm
+                .iter()
+                .map(|row| row.iter().map(|v| if *v { '#' } else { '.' }).collect())
+                .collect::<Vec<String>>()
+                .join("\n"),
+            _ => self.to_string(),
+        })
+    }
+}
+
+impl PartialEq for Answer {
+    /// Answers are equal if they would result in the same submission,
+    /// regardless of the variant used to store them.
+    fn eq(&self, other: &Self) -> bool {
+        if let (Some(a), Some(b)) = (self.as_integer(), other.as_integer()) {
+            return a == b;
+        }
+
+        match (self, other) {
+            (Self::Bitmap(a), Self::Bitmap(b)) => a == b,
+            _ => match (self.as_submission(), other.as_submission()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+        }
+    }
 }
 
 impl std::fmt::Display for Answer {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::Number(n) => write!(f, "{}", n),
+            Self::Signed(n) => write!(f, "{}", n),
+            Self::Big(n) => write!(f, "{}", n),
             Self::String(s) => write!(f, "{}", s),
-            Self::Bitmap(bm) => {
+            Self::List(l) => write!(
+                f,
+                "{}",
+                l.iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            Self::Bitmap(bm) => match ocr::recognize(bm) {
+                Some(letters) => write!(f, "{}", letters),
+                None => {
+                    writeln!(f).unwrap();
+                    write!(f, "{}", Self::minify_bitmap(bm))
+                }
+            },
+            Self::Grid(_) => {
                 writeln!(f).unwrap();
-                write!(f, "{}", Self::minify_bitmap(bm))
+                write!(
+                    f,
+                    "{}",
+                    Self::append_per_line(self.as_submission().unwrap(), "\t\t")
+                )
             }
         }
     }
 }
 
-impl From<u64> for Answer {
-    fn from(n: u64) -> Self {
-        Self::Number(n)
+/// The reasons a part can fail to produce an [`Answer`].
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// The search finished without finding a solution.
+    NoSolution,
+    /// The input is inconsistent or doesn't describe a valid state.
+    InvalidInput(String),
+    /// The part hasn't been solved yet.
+    Unimplemented,
+    /// The variant selected with `--variant` doesn't exist for this day.
+    UnknownVariant(String),
+    /// The extra part selected with `--part` doesn't exist for this day.
+    UnknownPart(String),
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::NoSolution => write!(f, "No solution found"),
+            Self::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+            Self::Unimplemented => write!(f, "Not implemented yet"),
+            Self::UnknownVariant(name) => write!(f, "There is no variant named \"{}\"", name),
+            Self::UnknownPart(name) => write!(f, "There is no extra part named \"{}\"", name),
+        }
     }
 }
 
+impl std::error::Error for SolveError {}
+
+macro_rules! impl_answer_from_int {
+    ($variant:ident, $target:ty, $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Self::$variant(n as $target)
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from_int!(Number, u64, u8, u16, u32, u64, usize);
+impl_answer_from_int!(Signed, i64, i8, i16, i32, i64, isize);
+impl_answer_from_int!(Big, u128, u128);
+
 impl From<String> for Answer {
     fn from(s: String) -> Self {
         Self::String(s)
     }
 }
 
-pub trait DayImpl<T>
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_owned())
+    }
+}
+
+impl<T> From<Vec<T>> for Answer
+where
+    T: Into<Answer>,
+{
+    fn from(l: Vec<T>) -> Self {
+        Self::List(l.into_iter().map(|v| v.into()).collect())
+    }
+}
+
+impl From<utils::Map<char>> for Answer {
+    fn from(map: utils::Map<char>) -> Self {
+        Self::Grid(map.into_raw_tiles())
+    }
+}
+
+/// An implementation of a part, see [`DayImpl::one_variants`].
+pub type PartFn<D, T, S> = fn(&D, &mut T, &S) -> Result<Answer, SolveError>;
+
+/// A named part beyond the two official ones, see [`DayImpl::extra_parts`].
+pub struct ExtraPart<D, T, S> {
+    pub name: &'static str,
+    pub solve: PartFn<D, T, S>,
+    /// The example of this part, `None` to test it with the example of the day.
+    pub example: Option<&'static str>,
+    /// The answer for the example.
+    pub expected: Answer,
+}
+
+/// A command working on a day's parsed input, see [`DayImpl::tools`].
+pub struct Tool<D, T> {
+    /// The subcommand, with the tool's name, description and arguments.
+    pub command: fn() -> App<'static, 'static>,
+    /// Runs the tool with its arguments, returning what to print or why it failed.
+    pub run: fn(&D, &T, &ArgMatches) -> Result<String, String>,
+}
+
+/// The name of the variant implemented by [`DayImpl::one`] and [`DayImpl::two`].
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// The result of a single variant of a part, see [`DayImpl::crosscheck`].
+#[derive(Debug, Clone)]
+pub struct VariantRun {
+    pub part: u8,
+    pub name: &'static str,
+    /// `None` if the variant panicked.
+    pub result: Option<Result<Answer, SolveError>>,
+    pub time: Duration,
+}
+
+/// A day's solution.
+///
+/// `'a` is the lifetime of the input string, so the parsed data may borrow from it.
+pub trait DayImpl<'a, T, S = ()>
 where
     T: Clone,
+    S: Default,
 {
-    /// Parses the test input.
+    /// Whether [`one`](DayImpl::one) modifies the data.
+    /// If not, part two reuses the same data instead of working on a clone.
+    const MUTATES_DATA: bool = true;
+
+    /// The example input of the puzzle, usually included from `test_inputs`.
+    const EXAMPLE: &'static str;
+
+    /// Parses the example, or the example it was overridden with by `test --watch`,
+    /// with the example's [`Parameters`].
     fn init_test() -> (Self, T)
     where
-        Self: Sized;
+        Self: Sized,
+    {
+        params::with_example(|| Self::init(input::example(Self::EXAMPLE)))
+    }
 
     fn expected_results() -> (Answer, Answer);
 
     /// Parse input
-    fn init(input: &str) -> (Self, T)
+    fn init(input: &'a str) -> (Self, T)
     where
         Self: Sized;
 
+    /// Compute results shared by both parts.
+    fn precompute(&self, _data: &T) -> S {
+        S::default()
+    }
+
     /// Compute part 1
-    fn one(&self, data: &mut T) -> Answer;
+    fn one(&self, data: &mut T, shared: &S) -> Result<Answer, SolveError>;
 
     /// Compute part 2
-    fn two(&self, data: &mut T) -> Answer;
+    fn two(&self, data: &mut T, shared: &S) -> Result<Answer, SolveError>;
+
+    /// Named alternative implementations of part 1, next to [`one`](DayImpl::one).
+    /// Used instead of it when selected with `--variant`.
+    fn one_variants() -> Vec<(&'static str, PartFn<Self, T, S>)>
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+
+    /// Named alternative implementations of part 2, next to [`two`](DayImpl::two).
+    fn two_variants() -> Vec<(&'static str, PartFn<Self, T, S>)>
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+
+    /// The variant of a part selected with `--variant`, falling back to `default` if only the other
+    /// part has a variant of that name.
+    fn selected_variant(
+        default: PartFn<Self, T, S>,
+        variants: Vec<(&'static str, PartFn<Self, T, S>)>,
+        other: Vec<(&'static str, PartFn<Self, T, S>)>,
+    ) -> Result<PartFn<Self, T, S>, SolveError>
+    where
+        Self: Sized,
+    {
+        let Some(name) = variants::selected() else {
+            return Ok(default);
+        };
+        if name == DEFAULT_VARIANT || other.iter().any(|(v, _)| *v == name) {
+            return Ok(default);
+        }
+        variants
+            .into_iter()
+            .find(|(v, _)| *v == name)
+            .map(|(_, f)| f)
+            .ok_or(SolveError::UnknownVariant(name))
+    }
+
+    /// Compute part 1 using the selected variant
+    fn solve_one(&self, data: &mut T, shared: &S) -> Result<Answer, SolveError>
+    where
+        Self: Sized,
+    {
+        Self::selected_variant(Self::one, Self::one_variants(), Self::two_variants())?(
+            self, data, shared,
+        )
+    }
+
+    /// Compute part 2 using the selected variant
+    fn solve_two(&self, data: &mut T, shared: &S) -> Result<Answer, SolveError>
+    where
+        Self: Sized,
+    {
+        Self::selected_variant(Self::two, Self::two_variants(), Self::one_variants())?(
+            self, data, shared,
+        )
+    }
+
+    /// Named parts beyond the two official ones, like harder variants of the puzzle.
+    /// Selected with `--part extra:name`.
+    fn extra_parts() -> Vec<ExtraPart<Self, T, S>>
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+
+    /// The names of the [extra parts](DayImpl::extra_parts).
+    fn extra_part_names() -> Vec<&'static str>
+    where
+        Self: Sized,
+    {
+        Self::extra_parts().iter().map(|v| v.name).collect()
+    }
+
+    /// The extra part with the given name.
+    fn extra_part(name: &str) -> Result<ExtraPart<Self, T, S>, SolveError>
+    where
+        Self: Sized,
+    {
+        Self::extra_parts()
+            .into_iter()
+            .find(|v| v.name == name)
+            .ok_or_else(|| SolveError::UnknownPart(name.to_string()))
+    }
+
+    /// Commands that work on the parsed input, like a rule checker.
+    /// Run with `aoc24 <day> tool <name>`.
+    fn tools() -> Vec<Tool<Self, T>>
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+
+    /// The subcommands of the [tools](DayImpl::tools).
+    fn tool_commands() -> Vec<App<'static, 'static>>
+    where
+        Self: Sized,
+    {
+        Self::tools().iter().map(|v| (v.command)()).collect()
+    }
+
+    /// Parses the input, or the example if there is none, and runs the tool selected in `matches`.
+    fn run_tool(input: Option<&'a str>, matches: &ArgMatches) -> Result<String, String>
+    where
+        Self: Sized,
+    {
+        let (name, Some(args)) = matches.subcommand() else {
+            return Err("No tool selected.".to_string());
+        };
+        let tool = Self::tools()
+            .into_iter()
+            .find(|v| (v.command)().get_name() == name)
+            .ok_or_else(|| format!("There is no tool named \"{}\".", name))?;
+
+        let (day, data) = match input {
+            Some(input) => Self::init(input),
+            None => Self::init_test(),
+        };
+        (tool.run)(&day, &data, args)
+    }
+
+    /// A map of the day's state, exported as image when requested.
+    fn exposed_map(&self, _data: &T, _shared: &S) -> Option<utils::Map<char>> {
+        None
+    }
+
+    /// The exposed map if `map` is set, taken before the parts run as they may change the data.
+    fn exposed_map_if(&self, map: bool, data: &T, shared: &S) -> Option<utils::Map<char>> {
+        if map {
+            self.exposed_map(data, shared)
+        } else {
+            None
+        }
+    }
 
     /// Parse input and measure the time it took
-    fn init_timed(input: &str) -> ((Self, T), Duration)
+    fn init_timed(input: &'a str) -> ((Self, T), Duration)
     where
         Self: Sized,
     {
+        let _span = info_span!("init").entered();
         let s = Instant::now();
         (Self::init(input), s.elapsed())
     }
 
+    /// Parse the test input and measure the time it took
+    fn init_test_timed() -> ((Self, T), Duration)
+    where
+        Self: Sized,
+    {
+        let _span = info_span!("init").entered();
+        let s = Instant::now();
+        (Self::init_test(), s.elapsed())
+    }
+
+    /// Compute the shared results and measure the time it took
+    fn precompute_timed(&self, data: &T) -> (S, Duration) {
+        let _span = info_span!("precompute").entered();
+        let s = Instant::now();
+        (self.precompute(data), s.elapsed())
+    }
+
     /// Compute part 1 and measure the time it took
-    fn one_timed(&self, data: &mut T) -> (Answer, Duration) {
+    fn one_timed(&self, data: &mut T, shared: &S) -> (Result<Answer, SolveError>, Duration)
+    where
+        Self: Sized,
+    {
+        let _span = info_span!("one").entered();
+        checkpoints::begin("one");
         let s = Instant::now();
-        (self.one(data), s.elapsed())
+        let one = self.solve_one(data, shared);
+        let t = s.elapsed();
+        checkpoints::end();
+        (one, t)
     }
 
     /// Compute part 2 and measure the time it took
-    fn two_timed(&self, data: &mut T) -> (Answer, Duration) {
+    fn two_timed(&self, data: &mut T, shared: &S) -> (Result<Answer, SolveError>, Duration)
+    where
+        Self: Sized,
+    {
+        let _span = info_span!("two").entered();
+        checkpoints::begin("two");
+        let s = Instant::now();
+        let two = self.solve_two(data, shared);
+        let t = s.elapsed();
+        checkpoints::end();
+        (two, t)
+    }
+
+    /// Compute an extra part and measure the time it took
+    fn extra_timed(
+        &self,
+        part: &ExtraPart<Self, T, S>,
+        data: &mut T,
+        shared: &S,
+    ) -> (Result<Answer, SolveError>, Duration)
+    where
+        Self: Sized,
+    {
+        let _span = info_span!("extra", name = part.name).entered();
+        checkpoints::begin("extra");
+        let s = Instant::now();
+        let extra = (part.solve)(self, data, shared);
+        let t = s.elapsed();
+        checkpoints::end();
+        (extra, t)
+    }
+
+    /// Compute part 1, on a copy of the data if part 1 modifies it
+    fn one_preserving(&self, data: &mut T, shared: &S) -> Result<Answer, SolveError>
+    where
+        Self: Sized,
+    {
+        if Self::MUTATES_DATA {
+            self.solve_one(&mut data.clone(), shared)
+        } else {
+            self.solve_one(data, shared)
+        }
+    }
+
+    /// Compute part 1 without affecting part 2 and measure the time it took,
+    /// including the time needed to copy the data.
+    fn one_preserving_timed(
+        &self,
+        data: &mut T,
+        shared: &S,
+    ) -> (Result<Answer, SolveError>, Duration)
+    where
+        Self: Sized,
+    {
+        let _span = info_span!("one").entered();
+        checkpoints::begin("one");
         let s = Instant::now();
-        (self.two(data), s.elapsed())
+        let one = self.one_preserving(data, shared);
+        let t = s.elapsed();
+        checkpoints::end();
+        (one, t)
     }
 
     /// Compute both parts
     #[allow(dead_code)]
-    fn run(input: &str) -> (Answer, Answer)
+    fn run(input: &'a str) -> (Result<Answer, SolveError>, Result<Answer, SolveError>)
     where
         Self: Sized,
     {
         let (day, mut data) = Self::init(input);
-        (day.one(&mut data.clone()), day.two(&mut data))
+        let shared = day.precompute(&data);
+        (
+            day.one_preserving(&mut data, &shared),
+            day.solve_two(&mut data, &shared),
+        )
     }
 
     /// Init and compute part 1
     #[allow(dead_code)]
-    fn run_one(input: &str) -> Answer
+    fn run_one(input: &'a str) -> Result<Answer, SolveError>
     where
         Self: Sized,
     {
         let (day, mut data) = Self::init(input);
-        day.one(&mut data)
+        let shared = day.precompute(&data);
+        day.solve_one(&mut data, &shared)
     }
 
     /// Init and compute part 1
     #[allow(dead_code)]
-    fn run_two(input: &str) -> Answer
+    fn run_two(input: &'a str) -> Result<Answer, SolveError>
     where
         Self: Sized,
     {
         let (day, mut data) = Self::init(input);
-        day.two(&mut data)
+        let shared = day.precompute(&data);
+        day.solve_two(&mut data, &shared)
     }
 
-    /// Init and compute part 1
-    fn run_one_timed(input: &str) -> (Answer, Duration, Duration)
+    /// Init and compute part 1, also returning the exposed map if `map` is set
+    #[allow(clippy::type_complexity)]
+    fn run_one_timed(
+        input: &'a str,
+        map: bool,
+    ) -> (
+        Result<Answer, SolveError>,
+        Duration,
+        Duration,
+        Duration,
+        Option<utils::Map<char>>,
+    )
     where
         Self: Sized,
     {
         let ((day, mut data), init_t) = Self::init_timed(input);
-        let (one, one_t) = day.one_timed(&mut data);
-        (one, init_t, one_t)
+        let (shared, pre_t) = day.precompute_timed(&data);
+        let map = day.exposed_map_if(map, &data, &shared);
+        let (one, one_t) = day.one_timed(&mut data, &shared);
+        (one, init_t, pre_t, one_t, map)
     }
 
-    /// Init and compute part 1
-    fn run_two_timed(input: &str) -> (Answer, Duration, Duration)
+    /// Init and compute part 2, also returning the exposed map if `map` is set
+    #[allow(clippy::type_complexity)]
+    fn run_two_timed(
+        input: &'a str,
+        map: bool,
+    ) -> (
+        Result<Answer, SolveError>,
+        Duration,
+        Duration,
+        Duration,
+        Option<utils::Map<char>>,
+    )
     where
         Self: Sized,
     {
         let ((day, mut data), init_t) = Self::init_timed(input);
-        let (two, two_t) = day.two_timed(&mut data);
-        (two, init_t, two_t)
+        let (shared, pre_t) = day.precompute_timed(&data);
+        let map = day.exposed_map_if(map, &data, &shared);
+        let (two, two_t) = day.two_timed(&mut data, &shared);
+        (two, init_t, pre_t, two_t, map)
     }
 
-    /// Compute both parts, and measure the time each step took
-    fn run_timed(input: &str) -> (Answer, Answer, Duration, Duration, Duration)
+    /// Init and compute an extra part, also returning the exposed map if `map` is set
+    #[allow(clippy::type_complexity)]
+    fn run_extra_timed(
+        input: &'a str,
+        name: &str,
+        map: bool,
+    ) -> (
+        Result<Answer, SolveError>,
+        Duration,
+        Duration,
+        Duration,
+        Option<utils::Map<char>>,
+    )
+    where
+        Self: Sized,
+    {
+        let part = match Self::extra_part(name) {
+            Ok(part) => part,
+            Err(err) => {
+                return (
+                    Err(err),
+                    Duration::ZERO,
+                    Duration::ZERO,
+                    Duration::ZERO,
+                    None,
+                );
+            }
+        };
+        let ((day, mut data), init_t) = Self::init_timed(input);
+        let (shared, pre_t) = day.precompute_timed(&data);
+        let map = day.exposed_map_if(map, &data, &shared);
+        let (extra, extra_t) = day.extra_timed(&part, &mut data, &shared);
+        (extra, init_t, pre_t, extra_t, map)
+    }
+
+    /// Compute both parts, and measure the time each step took,
+    /// also returning the exposed map if `map` is set
+    #[allow(clippy::type_complexity)]
+    fn run_timed(
+        input: &'a str,
+        map: bool,
+    ) -> (
+        Result<Answer, SolveError>,
+        Result<Answer, SolveError>,
+        Duration,
+        Duration,
+        Duration,
+        Duration,
+        Option<utils::Map<char>>,
+    )
     where
         Self: Sized,
     {
         let ((day, mut data), i_t) = Self::init_timed(input);
-        let (one, one_t) = day.one_timed(&mut data.clone());
-        let (two, two_t) = day.two_timed(&mut data);
+        let (shared, pre_t) = day.precompute_timed(&data);
+        let map = day.exposed_map_if(map, &data, &shared);
+        let (one, one_t) = day.one_preserving_timed(&mut data, &shared);
+        let (two, two_t) = day.two_timed(&mut data, &shared);
+
+        (one, two, i_t, pre_t, one_t, two_t, map)
+    }
+
+    /// Runs every variant of both parts on the same data, each on its own copy.
+    /// Panics are caught, so the other variants still run.
+    fn crosscheck_data(&self, data: &T) -> Vec<VariantRun>
+    where
+        Self: Sized,
+    {
+        let shared = self.precompute(data);
+        let mut runs = vec![];
+
+        for (part, default, variants) in [
+            (1, Self::one as PartFn<Self, T, S>, Self::one_variants()),
+            (2, Self::two as PartFn<Self, T, S>, Self::two_variants()),
+        ] {
+            for (name, f) in [(DEFAULT_VARIANT, default)].into_iter().chain(variants) {
+                let _span = info_span!("variant", part, name).entered();
+                let mut data = data.clone();
+                let s = Instant::now();
+                let result =
+                    panic::catch_unwind(AssertUnwindSafe(|| f(self, &mut data, &shared))).ok();
+                runs.push(VariantRun {
+                    part,
+                    name,
+                    result,
+                    time: s.elapsed(),
+                });
+            }
+        }
+
+        runs
+    }
+
+    /// Parse input and run every variant of both parts
+    fn crosscheck(input: &'a str) -> Vec<VariantRun>
+    where
+        Self: Sized,
+    {
+        let (day, data) = Self::init(input);
+        day.crosscheck_data(&data)
+    }
+
+    /// Parse the test input and run every variant of both parts
+    fn crosscheck_test() -> Vec<VariantRun>
+    where
+        Self: Sized,
+    {
+        let (day, data) = Self::init_test();
+        day.crosscheck_data(&data)
+    }
 
-        (one, two, i_t, one_t, two_t)
+    /// Parse the test input and get the exposed map
+    fn render_test_map() -> Option<utils::Map<char>>
+    where
+        Self: Sized,
+    {
+        let (day, data) = Self::init_test();
+        let shared = day.precompute(&data);
+        day.exposed_map(&data, &shared)
     }
 
     /// Test part one
-    fn test_one() -> (bool, Answer, Answer)
+    fn test_one() -> (bool, Result<Answer, SolveError>, Answer)
     where
         Self: Sized,
     {
-        let (day, mut data) = Self::init_test();
-        let one = day.one(&mut data);
+        let ((day, mut data), _) = Self::init_test_timed();
+        let (shared, _) = day.precompute_timed(&data);
+        let (one, _) = day.one_timed(&mut data, &shared);
 
         let (one_e, _) = Self::expected_results();
 
-        (one_e == one, one, one_e)
+        (one.as_ref() == Ok(&one_e), one, one_e)
     }
 
     /// Test part two
-    fn test_two() -> (bool, Answer, Answer)
+    fn test_two() -> (bool, Result<Answer, SolveError>, Answer)
     where
         Self: Sized,
     {
-        let (day, mut data) = Self::init_test();
-        let two = day.two(&mut data);
+        let ((day, mut data), _) = Self::init_test_timed();
+        let (shared, _) = day.precompute_timed(&data);
+        let (two, _) = day.two_timed(&mut data, &shared);
 
         let (_, two_e) = Self::expected_results();
 
-        (two_e == two, two, two_e)
+        (two.as_ref() == Ok(&two_e), two, two_e)
+    }
+
+    /// Test an extra part
+    fn test_extra(name: &str) -> (bool, Result<Answer, SolveError>, Answer)
+    where
+        Self: Sized,
+    {
+        let part = match Self::extra_part(name) {
+            Ok(part) => part,
+            Err(err) => return (false, Err(err), Answer::String(String::new())),
+        };
+        // An override replaces the example of the day, not the ones of extra parts with their own.
+        let example = match part.example {
+            Some(example) => input::normalize_example(example),
+            None => input::example(Self::EXAMPLE),
+        };
+        let (day, mut data) = params::with_example(|| Self::init(example));
+        let (shared, _) = day.precompute_timed(&data);
+        let (extra, _) = day.extra_timed(&part, &mut data, &shared);
+
+        (extra.as_ref() == Ok(&part.expected), extra, part.expected)
     }
 
     /// Run both tests
-    fn test() -> ((bool, Answer, Answer), (bool, Answer, Answer))
+    #[allow(clippy::type_complexity)]
+    fn test() -> (
+        (bool, Result<Answer, SolveError>, Answer),
+        (bool, Result<Answer, SolveError>, Answer),
+    )
     where
         Self: Sized,
     {
-        let (day, mut data) = Self::init_test();
-        let one = day.one(&mut data.clone());
-        let two = day.two(&mut data);
+        let ((day, mut data), _) = Self::init_test_timed();
+        let (shared, _) = day.precompute_timed(&data);
+        let (one, _) = day.one_preserving_timed(&mut data, &shared);
+        let (two, _) = day.two_timed(&mut data, &shared);
 
         let (one_e, two_e) = Self::expected_results();
 
-        ((one_e == one, one, one_e), (two_e == two, two, two_e))
+        (
+            (one.as_ref() == Ok(&one_e), one, one_e),
+            (two.as_ref() == Ok(&two_e), two, two_e),
+        )
+    }
+}
+
+/// Produces random, valid inputs for a day, which the `fuzz` command runs the solution on.
+pub trait InputGenerator {
+    /// Generates an input from the random numbers of `rng`.
+    /// `size` roughly scales the input, like the number of lines or the width of a map.
+    fn generate(rng: &mut Rng, size: usize) -> String;
+
+    /// Smaller variants of a failing input, tried in order while shrinking it.
+    fn shrink(input: &str) -> Vec<String> {
+        shrink_lines(input)
+    }
+}
+
+/// The functions of a day's [`InputGenerator`].
+pub struct Generator {
+    pub generate: fn(&mut Rng, usize) -> String,
+    pub shrink: fn(&str) -> Vec<String>,
+}
+
+/// The input generator of a day, if it implements [`InputGenerator`].
+pub fn input_generator(day: u8) -> Option<Generator> {
+    fn of<D: InputGenerator>() -> Option<Generator> {
+        Some(Generator {
+            generate: D::generate,
+            shrink: D::shrink,
+        })
+    }
+
+    match day {
+        1 => of::<Day<1>>(),
+        2 => of::<Day<2>>(),
+        5 => of::<Day<5>>(),
+        6 => of::<Day<6>>(),
+        7 => of::<Day<7>>(),
+        _ => None,
+    }
+}
+
+/// Transformations of the input that don't change a day's answers,
+/// which the `metamorphic` command checks.
+pub trait Metamorphic {
+    const TRANSFORMS: &'static [Transform];
+    /// Whether `^>v<` in the input are directions, which turn along with the grid.
+    const ARROWS: bool = false;
+}
+
+/// The metamorphic relations of a day, together with its example.
+pub struct Relations {
+    pub transforms: &'static [Transform],
+    pub arrows: bool,
+    pub example: &'static str,
+}
+
+/// The metamorphic relations of a day, if it implements [`Metamorphic`].
+pub fn metamorphic_relations(day: u8) -> Option<Relations> {
+    fn of<D: Metamorphic>(example: &'static str) -> Option<Relations> {
+        Some(Relations {
+            transforms: D::TRANSFORMS,
+            arrows: D::ARROWS,
+            example,
+        })
+    }
+
+    match day {
+        1 => of::<Day<1>>(<Day<1> as DayImpl<_, _>>::EXAMPLE),
+        2 => of::<Day<2>>(<Day<2> as DayImpl<_, _>>::EXAMPLE),
+        4 => of::<Day<4>>(<Day<4> as DayImpl<_, _>>::EXAMPLE),
+        6 => of::<Day<6>>(<Day<6> as DayImpl<_, _>>::EXAMPLE),
+        7 => of::<Day<7>>(<Day<7> as DayImpl<_, _>>::EXAMPLE),
+        8 => of::<Day<8>>(<Day<8> as DayImpl<_, _>>::EXAMPLE),
+        _ => None,
+    }
+}
+
+/// Puzzle constants that differ between the example and the real input, like a grid size.
+///
+/// Days read them with [`current`](Parameters::current) while parsing,
+/// and they can be overridden with `--param key=value`.
+pub trait Parameters: Sized {
+    /// The names of the parameters, which [`set`](Parameters::set) accepts.
+    const KEYS: &'static [&'static str];
+
+    fn example() -> Self;
+
+    fn real() -> Self;
+
+    /// The parameters of inputs made by an [`InputGenerator`], the real ones by default.
+    fn generated() -> Self {
+        Self::real()
+    }
+
+    /// Sets a parameter from its value on the command line.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String>;
+
+    /// Applies overrides, failing on unknown keys or values that don't fit.
+    fn with_overrides(mut self, overrides: &[(String, String)]) -> Result<Self, String> {
+        for (key, value) in overrides {
+            if !Self::KEYS.contains(&key.as_str()) {
+                return Err(format!(
+                    "There is no parameter named \"{}\", use one of {}.",
+                    key,
+                    Self::KEYS.join(", ")
+                ));
+            }
+            self.set(key, value)
+                .map_err(|err| format!("Invalid value \"{}\" for {}: {}", value, key, err))?;
+        }
+        Ok(self)
+    }
+
+    /// The parameters of the input being parsed, with the overrides applied.
+    fn current() -> Self {
+        let defaults = match params::source() {
+            Source::Real => Self::real(),
+            Source::Example => Self::example(),
+            Source::Generated => Self::generated(),
+        };
+        // The overrides were checked when they were selected.
+        defaults.with_overrides(&params::overrides()).unwrap()
+    }
+}
+
+/// Checks `--param` overrides against the [`Parameters`] of a day.
+pub fn check_parameters(day: u8, overrides: &[(String, String)]) -> Result<(), String> {
+    fn of<P: Parameters>(overrides: &[(String, String)]) -> Result<(), String> {
+        P::example().with_overrides(overrides).map(|_| ())
+    }
+
+    match day {
+        6 => of::<d06::LabSize>(overrides),
+        _ if overrides.is_empty() => Ok(()),
+        _ => Err(format!("Day {} has no parameters.", day)),
     }
 }
 
 mod_days!();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_equal_across_variants() {
+        assert_eq!(Answer::Number(42), Answer::Signed(42));
+        assert_eq!(Answer::Signed(42), Answer::Big(42));
+        assert_eq!(Answer::Big(u64::MAX as u128), Answer::Number(u64::MAX));
+        assert_ne!(Answer::Signed(-1), Answer::Number(1));
+        assert_ne!(Answer::Big(u128::MAX), Answer::Number(u64::MAX));
+    }
+
+    #[test]
+    fn integers_equal_their_submission() {
+        assert_eq!(Answer::Signed(-7), Answer::from("-7"));
+        assert_eq!(Answer::from("-7"), Answer::Signed(-7));
+        assert_eq!(Answer::Big(u128::MAX), Answer::from(u128::MAX.to_string()));
+        assert_eq!(Answer::Number(12), Answer::from(vec![12u64]));
+        assert_ne!(Answer::Number(12), Answer::from("012"));
+    }
+
+    #[test]
+    fn lists_equal_their_submission() {
+        assert_eq!(Answer::from(vec![1i64, -2, 3]), Answer::from("1,-2,3"));
+        assert_ne!(Answer::from(vec![1u64, 2]), Answer::from("1, 2"));
+    }
+
+    #[test]
+    fn unreadable_bitmaps_only_equal_themselves() {
+        let bm = Answer::Bitmap(vec![vec![true]]);
+        assert_eq!(bm, bm.clone());
+        assert_ne!(bm, Answer::from("#"));
+        assert_ne!(Answer::from("#"), bm);
+    }
+}
@@ -0,0 +1,213 @@
+//! Recognition of the letters AoC draws using pixels, as seen in some `Answer::Bitmap`s.
+//!
+//! Two fonts are known: the small one with 6 rows per glyph, and the large one with 10 rows.
+//! Glyphs are separated by at least one empty column, so they are matched after splitting
+//! the bitmap on these.
+
+const SMALL_FONT: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const LARGE_FONT: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+fn parse_glyph(glyph: &str) -> Vec<Vec<bool>> {
+    glyph
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect()
+}
+
+fn is_column_empty(bm: &[Vec<bool>], x: usize) -> bool {
+    bm.iter().all(|row| !row.get(x).copied().unwrap_or(false))
+}
+
+/// Removes empty columns on the left and right of a glyph.
+fn trim_columns(bm: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let width = bm.iter().map(|row| row.len()).max().unwrap_or(0);
+    let first = (0..width).find(|x| !is_column_empty(bm, *x));
+    let last = (0..width).rev().find(|x| !is_column_empty(bm, *x));
+
+    match (first, last) {
+        (Some(first), Some(last)) => bm
+            .iter()
+            .map(|row| {
+                (first..=last)
+                    .map(|x| row.get(x).copied().unwrap_or(false))
+                    .collect()
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn match_glyph(glyph: &[Vec<bool>], font: &[(char, &str)]) -> Option<char> {
+    font.iter()
+        .find(|(_, pattern)| trim_columns(&parse_glyph(pattern)) == glyph)
+        .map(|(letter, _)| *letter)
+}
+
+/// Reads the letters drawn in a bitmap.
+///
+/// Returns None if the bitmap doesn't have the height of a known font,
+/// or contains a glyph which isn't known.
+pub fn recognize(bm: &[Vec<bool>]) -> Option<String> {
+    let first_row = bm.iter().position(|row| row.iter().any(|v| *v))?;
+    let last_row = bm.iter().rposition(|row| row.iter().any(|v| *v))?;
+    let bm = &bm[first_row..=last_row];
+
+    let font = match bm.len() {
+        6 => SMALL_FONT,
+        10 => LARGE_FONT,
+        _ => return None,
+    };
+
+    let width = bm.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut letters = String::new();
+    let mut start: Option<usize> = None;
+
+    for x in 0..=width {
+        match (start, x == width || is_column_empty(bm, x)) {
+            (None, false) => start = Some(x),
+            (Some(s), true) => {
+                let glyph: Vec<Vec<bool>> = bm
+                    .iter()
+                    .map(|row| {
+                        (s..x)
+                            .map(|x| row.get(x).copied().unwrap_or(false))
+                            .collect()
+                    })
+                    .collect();
+                letters.push(match_glyph(&trim_columns(&glyph), font)?);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    Some(letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws a word in a font, with one empty column between the letters.
+    fn render(word: &str, font: &[(char, &str)]) -> Vec<Vec<bool>> {
+        let glyphs: Vec<Vec<Vec<bool>>> = word
+            .chars()
+            .map(|c| parse_glyph(font.iter().find(|(letter, _)| *letter == c).unwrap().1))
+            .collect();
+
+        (0..glyphs[0].len())
+            .map(|y| {
+                glyphs
+                    .iter()
+                    .flat_map(|glyph| glyph[y].iter().copied().chain([false]))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recognizes_every_letter() {
+        for font in [SMALL_FONT, LARGE_FONT] {
+            let word: String = font.iter().map(|(letter, _)| *letter).collect();
+            assert_eq!(recognize(&render(&word, font)), Some(word));
+        }
+    }
+
+    #[test]
+    fn ignores_empty_rows() {
+        let mut bm = render("HI", SMALL_FONT);
+        bm.insert(0, vec![false; bm[0].len()]);
+        bm.push(vec![]);
+        assert_eq!(recognize(&bm).as_deref(), Some("HI"));
+    }
+
+    #[test]
+    fn rejects_unknown_glyphs() {
+        let mut bm = render("HI", SMALL_FONT);
+        bm[0][0] = false;
+        assert_eq!(recognize(&bm), None);
+        assert_eq!(recognize(&bm[..5]), None);
+        assert_eq!(recognize(&[]), None);
+    }
+}
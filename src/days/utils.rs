@@ -35,6 +35,11 @@ impl<T> Map<T> {
     pub fn get_raw_tiles_mut(&mut self) -> &mut Vec<Vec<T>> {
         &mut self.tiles
     }
+
+    /// Consumes the map, returning the internally stored tile data.
+    pub fn into_raw_tiles(self) -> Vec<Vec<T>> {
+        self.tiles
+    }
 }
 
 impl<T> From<Vec<Vec<T>>> for Map<T> {
@@ -0,0 +1,414 @@
+use crate::tui::RawTerminal;
+use crate::visualizer::Frame;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    queue,
+    style::{Print, Stylize},
+    terminal::{self, ClearType},
+};
+use lazy_static::lazy_static;
+use mut_static::MutStatic;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A recorded state of a day.
+#[derive(Debug, Clone, Default)]
+pub struct Step {
+    /// The pretty printed [`Debug`] output of the state.
+    pub state: String,
+    pub frame: Option<Frame>,
+    /// The names of the breakpoints that triggered on this step.
+    pub breakpoints: Vec<&'static str>,
+}
+
+/// Every this many frames one is stored whole, the others only store what changed.
+const KEYFRAME_INTERVAL: usize = 256;
+
+/// A frame as stored in the history.
+#[derive(Debug)]
+enum StoredFrame {
+    Key(Frame),
+    /// The tiles that changed since the previous frame, as `(x, y, tile)`.
+    Delta {
+        changes: Vec<(usize, usize, char)>,
+        highlights: Vec<(usize, usize)>,
+    },
+}
+
+/// A [`Step`] as stored in the history.
+#[derive(Debug)]
+struct RecordedStep {
+    state: String,
+    frame: Option<StoredFrame>,
+    breakpoints: Vec<&'static str>,
+}
+
+struct History {
+    steps: Vec<RecordedStep>,
+    max_steps: usize,
+    truncated: bool,
+    /// The last recorded frame, which the next one is compared to.
+    last_frame: Option<Frame>,
+    frames_since_key: usize,
+}
+
+fn same_size(a: &[Vec<char>], b: &[Vec<char>]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.len() == b.len())
+}
+
+impl History {
+    fn store_frame(&mut self, frame: Frame) -> StoredFrame {
+        let stored = match &self.last_frame {
+            Some(last)
+                if self.frames_since_key + 1 < KEYFRAME_INTERVAL
+                    && same_size(&last.grid, &frame.grid) =>
+            {
+                self.frames_since_key += 1;
+                StoredFrame::Delta {
+                    changes: frame
+                        .grid
+                        .iter()
+                        .zip(&last.grid)
+                        .enumerate()
+                        .flat_map(|(y, (row, last_row))| {
+                            row.iter()
+                                .zip(last_row)
+                                .enumerate()
+                                .filter(|(_, (c, last))| c != last)
+                                .map(move |(x, (c, _))| (x, y, *c))
+                        })
+                        .collect(),
+                    highlights: frame.highlights.clone(),
+                }
+            }
+            _ => {
+                self.frames_since_key = 0;
+                StoredFrame::Key(frame.clone())
+            }
+        };
+        self.last_frame = Some(frame);
+        stored
+    }
+}
+
+lazy_static! {
+    static ref HISTORY: MutStatic<Option<History>> = MutStatic::from(None);
+}
+
+/// Mirrors whether [`HISTORY`] takes steps, so days don't lock it on every step.
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+/// Starts recording the steps of days that support it, keeping at most `max_steps`.
+pub fn enable(max_steps: usize) {
+    *HISTORY.write().unwrap() = Some(History {
+        steps: vec![],
+        max_steps,
+        truncated: false,
+        last_frame: None,
+        frames_since_key: 0,
+    });
+    RECORDING.store(true, Ordering::Relaxed);
+}
+
+/// Whether the debugger was enabled, even if it stopped recording after `max_steps`.
+pub fn is_enabled() -> bool {
+    HISTORY.read().unwrap().is_some()
+}
+
+/// Whether steps are currently recorded.
+pub fn is_recording() -> bool {
+    RECORDING.load(Ordering::Relaxed)
+}
+
+/// Adds a step to the history.
+///
+/// Prefer the [`debug_step!`](crate::debug_step) macro, which doesn't build the step when not recording.
+pub fn record(step: Step) {
+    if let Some(history) = HISTORY.write().unwrap().as_mut() {
+        if history.steps.len() >= history.max_steps {
+            history.truncated = true;
+            history.last_frame = None;
+            RECORDING.store(false, Ordering::Relaxed);
+        } else {
+            let frame = step.frame.map(|v| history.store_frame(v));
+            history.steps.push(RecordedStep {
+                state: step.state,
+                frame,
+                breakpoints: step.breakpoints,
+            });
+        }
+    }
+}
+
+/// Which breakpoints pause the stepping with `c`.
+#[derive(Debug, Clone, PartialEq)]
+enum BreakpointFilter {
+    All,
+    Only(&'static str),
+    None,
+}
+
+impl BreakpointFilter {
+    fn matches(&self, step: &RecordedStep) -> bool {
+        match self {
+            Self::All => !step.breakpoints.is_empty(),
+            Self::Only(name) => step.breakpoints.contains(name),
+            Self::None => false,
+        }
+    }
+
+    /// Cycles from all breakpoints, through each single one, to none.
+    fn next(&self, names: &[&'static str]) -> Self {
+        match self {
+            Self::All => names.first().map_or(Self::None, |v| Self::Only(v)),
+            Self::Only(name) => match names.iter().position(|v| v == name) {
+                Some(i) if i + 1 < names.len() => Self::Only(names[i + 1]),
+                _ => Self::None,
+            },
+            Self::None => Self::All,
+        }
+    }
+}
+
+impl std::fmt::Display for BreakpointFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::Only(name) => write!(f, "{}", name),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
+struct Browser {
+    steps: Vec<RecordedStep>,
+    truncated: bool,
+    current: usize,
+    filter: BreakpointFilter,
+    breakpoint_names: Vec<&'static str>,
+    /// The step number typed after pressing `g`.
+    jump_input: Option<String>,
+    scroll: usize,
+    message: String,
+}
+
+impl Browser {
+    fn new(steps: Vec<RecordedStep>, truncated: bool) -> Self {
+        let mut breakpoint_names: Vec<&'static str> = vec![];
+        for name in steps.iter().flat_map(|v| v.breakpoints.iter()) {
+            if !breakpoint_names.contains(name) {
+                breakpoint_names.push(name);
+            }
+        }
+
+        Self {
+            steps,
+            truncated,
+            current: 0,
+            filter: BreakpointFilter::All,
+            breakpoint_names,
+            jump_input: None,
+            scroll: 0,
+            message: String::new(),
+        }
+    }
+
+    /// Rebuilds the frame of a step from the last whole frame before it.
+    fn frame_at(&self, index: usize) -> Option<Frame> {
+        self.steps[index].frame.as_ref()?;
+        let (key, mut frame) = (0..=index).rev().find_map(|i| match &self.steps[i].frame {
+            Some(StoredFrame::Key(frame)) => Some((i, frame.clone())),
+            _ => None,
+        })?;
+        for step in &self.steps[key + 1..=index] {
+            if let Some(StoredFrame::Delta {
+                changes,
+                highlights,
+            }) = &step.frame
+            {
+                for (x, y, c) in changes {
+                    frame.grid[*y][*x] = *c;
+                }
+                frame.highlights = highlights.clone();
+            }
+        }
+        Some(frame)
+    }
+
+    fn go_to(&mut self, step: usize) {
+        self.current = step.min(self.steps.len() - 1);
+        self.scroll = 0;
+    }
+
+    /// Moves to the next (or previous) step where a breakpoint triggered.
+    fn continue_to_breakpoint(&mut self, forward: bool) {
+        let found = if forward {
+            (self.current + 1..self.steps.len()).find(|i| self.filter.matches(&self.steps[*i]))
+        } else {
+            (0..self.current)
+                .rev()
+                .find(|i| self.filter.matches(&self.steps[*i]))
+        };
+
+        match found {
+            Some(i) => {
+                self.go_to(i);
+                self.message = format!("Breakpoint: {}", self.steps[i].breakpoints.join(", "));
+            }
+            None => {
+                self.go_to(if forward { self.steps.len() - 1 } else { 0 });
+                self.message = "No breakpoint triggered.".to_string();
+            }
+        }
+    }
+
+    /// Handles a key press, returns false if the debugger should be closed.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.message.clear();
+
+        if let Some(input) = self.jump_input.as_mut() {
+            match key.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    if let Ok(step) = input.parse::<usize>() {
+                        self.go_to(step.saturating_sub(1));
+                    }
+                    self.jump_input = None;
+                }
+                KeyCode::Esc => self.jump_input = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Right | KeyCode::Char('n') | KeyCode::Char('l') => {
+                self.go_to(self.current + 1)
+            }
+            KeyCode::Left | KeyCode::Char('p') | KeyCode::Char('h') => {
+                self.go_to(self.current.saturating_sub(1))
+            }
+            KeyCode::Home => self.go_to(0),
+            KeyCode::End => self.go_to(self.steps.len() - 1),
+            KeyCode::Char('g') => self.jump_input = Some(String::new()),
+            KeyCode::Char('c') => self.continue_to_breakpoint(true),
+            KeyCode::Char('C') => self.continue_to_breakpoint(false),
+            KeyCode::Char('b') => self.filter = self.filter.next(&self.breakpoint_names),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll += 1,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (width as usize, height as usize);
+        let step = &self.steps[self.current];
+
+        let mut lines: Vec<String> = vec![];
+        lines.push(
+            format!(
+                "Step {}/{}{}  breakpoints: {}{}",
+                self.current + 1,
+                self.steps.len(),
+                if self.truncated { " (truncated)" } else { "" },
+                self.filter,
+                if step.breakpoints.is_empty() {
+                    String::new()
+                } else {
+                    format!("  triggered: {}", step.breakpoints.join(", "))
+                }
+            )
+            .bold()
+            .to_string(),
+        );
+
+        if let Some(frame) = &self.frame_at(self.current) {
+            for (y, row) in frame.grid.iter().enumerate() {
+                lines.push(
+                    row.iter()
+                        .take(width)
+                        .enumerate()
+                        .map(|(x, c)| {
+                            if frame.highlights.contains(&(x, y)) {
+                                c.to_string().reverse().to_string()
+                            } else {
+                                c.to_string()
+                            }
+                        })
+                        .collect(),
+                );
+            }
+            lines.push("-".repeat(width));
+        }
+
+        let footer = match &self.jump_input {
+            Some(input) => format!("Go to step: {}", input),
+            None if !self.message.is_empty() => self.message.clone(),
+            None => {
+                "←/→ step  g jump  c/C next/previous breakpoint  b breakpoints  ↑/↓ scroll  q quit"
+                    .to_string()
+            }
+        };
+
+        let state_rows = height.saturating_sub(lines.len() + 1);
+        lines.extend(
+            step.state
+                .lines()
+                .skip(self.scroll)
+                .take(state_rows)
+                .map(|v| v.chars().take(width).collect::<String>()),
+        );
+
+        queue!(out, terminal::Clear(ClearType::All))?;
+        for (row, line) in lines.iter().take(height.saturating_sub(1)).enumerate() {
+            queue!(out, cursor::MoveTo(0, row as u16), Print(line))?;
+        }
+        queue!(
+            out,
+            cursor::MoveTo(0, height.saturating_sub(1) as u16),
+            Print(footer.chars().take(width).collect::<String>().dim())
+        )?;
+        out.flush()
+    }
+
+    fn run(&mut self) -> std::io::Result<()> {
+        let mut out = std::io::stdout();
+        loop {
+            self.draw(&mut out)?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !self.handle_key(key)
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Opens the interactive debugger on the recorded history, if any steps were recorded.
+pub fn browse() -> std::io::Result<()> {
+    let history = match HISTORY.write().unwrap().as_mut() {
+        Some(history) => {
+            history.last_frame = None;
+            (std::mem::take(&mut history.steps), history.truncated)
+        }
+        None => return Ok(()),
+    };
+
+    let (steps, truncated) = history;
+    if steps.is_empty() {
+        println!("No steps were recorded, this day doesn't support the debugger.");
+        return Ok(());
+    }
+
+    let mut browser = Browser::new(steps, truncated);
+    let _terminal = RawTerminal::enter()?;
+    browser.run()
+}
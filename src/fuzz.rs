@@ -0,0 +1,295 @@
+use crate::days::{SolveError, input_generator};
+use crate::{Part, params, solve_day, variants};
+use colored::*;
+use std::io::{self, Read, Write};
+use std::panic;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A small deterministic random number generator (SplitMix64),
+/// so that a seed always produces the same input.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, or 0 if `n` is 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n == 0 { 0 } else { self.next_u64() % n }
+    }
+
+    /// A number in `min..=max`.
+    pub fn range(&mut self, min: u64, max: u64) -> u64 {
+        min + self.below(max - min + 1)
+    }
+
+    /// True with a probability of `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64 <= p
+    }
+}
+
+/// Smaller variants of an input, made by removing chunks of lines, largest chunks first.
+pub fn shrink_lines(input: &str) -> Vec<String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut candidates = vec![];
+
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        for start in (0..lines.len()).step_by(chunk) {
+            let rest: Vec<&str> = lines[..start]
+                .iter()
+                .chain(lines[(start + chunk).min(lines.len())..].iter())
+                .copied()
+                .collect();
+            if !rest.is_empty() {
+                candidates.push(rest.join("\n"));
+            }
+        }
+        chunk /= 2;
+    }
+
+    candidates
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzSettings {
+    pub runs: usize,
+    /// The seed of the first input, the others use the following seeds.
+    pub seed: u64,
+    pub max_size: usize,
+    pub timeout: Duration,
+}
+
+/// Why a solution failed on an input.
+#[derive(Debug, Clone)]
+enum Failure {
+    Panic(String),
+    Timeout,
+    InvalidInput(String),
+}
+
+impl Failure {
+    fn same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Panic(message) => write!(f, "{}", message),
+            Self::Timeout => write!(f, "timed out"),
+            Self::InvalidInput(message) => write!(f, "rejected a valid input: {}", message),
+        }
+    }
+}
+
+/// The hidden subcommand that runs a single input for [`fuzz`], see [`check_stdin`].
+pub const CHECK_COMMAND: &str = "fuzz-check";
+
+/// Reads a pipe of a child process on a separate thread, so the child never blocks on a full pipe.
+fn read_pipe(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = vec![];
+        let _ = pipe.read_to_end(&mut output);
+        String::from_utf8_lossy(&output).into_owned()
+    })
+}
+
+/// Runs both parts in a child process running [`check_stdin`],
+/// so panics are caught and a run that times out is killed instead of left running.
+fn check(day: u8, input: &str, timeout: Duration) -> Option<Failure> {
+    let mut command =
+        Command::new(std::env::current_exe().expect("Couldn't find the aoc24 binary"));
+    command.arg(day.to_string());
+    for (key, value) in params::overrides() {
+        command.arg("--param").arg(format!("{}={}", key, value));
+    }
+    if let Some(variant) = variants::selected() {
+        command.arg("--variant").arg(variant);
+    }
+    let mut child = command
+        .arg(CHECK_COMMAND)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Couldn't start the aoc24 binary");
+
+    // The child reads all of its input before solving, so this can't block on a full pipe.
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    let stdout = read_pipe(child.stdout.take().unwrap());
+    let stderr = read_pipe(child.stderr.take().unwrap());
+
+    let start = Instant::now();
+    let status = loop {
+        match child
+            .try_wait()
+            .expect("Couldn't wait for the aoc24 binary")
+        {
+            Some(status) => break status,
+            None if start.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Some(Failure::Timeout);
+            }
+            None => thread::sleep(Duration::from_millis(1)),
+        }
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    if !status.success() {
+        let message = stderr.trim();
+        return Some(Failure::Panic(if message.is_empty() {
+            format!("exited with {}", status)
+        } else {
+            message.to_string()
+        }));
+    }
+    stdout
+        .lines()
+        .last()
+        .and_then(|v| serde_json::from_str::<Option<String>>(v).ok())
+        .flatten()
+        .map(Failure::InvalidInput)
+}
+
+/// Runs both parts on the input read from stdin, in the child process started by [`fuzz`].
+///
+/// Prints the message of the first part that rejected the input as JSON, or `null`,
+/// as the last line. Panics are printed to stderr without a backtrace note.
+pub fn check_stdin(day: u8) {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .expect("Error while reading the input from stdin");
+
+    panic::set_hook(Box::new(|info| eprintln!("{}", info)));
+    let run = params::with_generated(|| solve_day(day, Part::Both, &input));
+    let rejected = [run.one, run.two].into_iter().find_map(|v| match v {
+        Err(SolveError::InvalidInput(message)) => Some(message),
+        _ => None,
+    });
+    println!("{}", serde_json::to_string(&rejected).unwrap());
+}
+
+/// The number of shrinking candidates that may time out before shrinking stops,
+/// as each of them takes the whole timeout.
+const MAX_SHRINK_TIMEOUTS: usize = 8;
+
+/// Repeatedly replaces the input with its first smaller variant that fails the same way.
+fn shrink(
+    day: u8,
+    input: String,
+    failure: &Failure,
+    shrinker: fn(&str) -> Vec<String>,
+    timeout: Duration,
+) -> (String, usize) {
+    let mut input = input;
+    let mut steps = 0;
+    let mut timeouts = 0;
+
+    'outer: loop {
+        for candidate in shrinker(&input) {
+            let result = check(day, &candidate, timeout);
+            if matches!(result, Some(Failure::Timeout)) {
+                timeouts += 1;
+            }
+            if result.is_some_and(|v| v.same_kind(failure)) {
+                input = candidate;
+                steps += 1;
+                continue 'outer;
+            }
+            if timeouts >= MAX_SHRINK_TIMEOUTS {
+                break 'outer;
+            }
+        }
+        break;
+    }
+
+    (input, steps)
+}
+
+/// Runs a day on generated inputs until one fails, then shrinks and prints that input.
+///
+/// Returns whether all inputs passed.
+pub fn fuzz(day: u8, settings: &FuzzSettings) -> bool {
+    let Some(generator) = input_generator(day) else {
+        println!(
+            "{}",
+            format!("Day {} has no input generator.", day).red().bold()
+        );
+        return false;
+    };
+
+    println!(
+        "{} Day {} on {} inputs, seed {}",
+        "Fuzzing".green().bold(),
+        day,
+        settings.runs,
+        settings.seed
+    );
+    println!("{}", "-----------------------".green().bold());
+
+    let mut failed = None;
+    for run in 0..settings.runs {
+        let seed = settings.seed.wrapping_add(run as u64);
+        let mut rng = Rng::new(seed);
+        let size = 1 + rng.below(settings.max_size as u64) as usize;
+        let input = (generator.generate)(&mut rng, size);
+
+        if let Some(failure) = check(day, &input, settings.timeout) {
+            failed = Some((seed, size, input, failure));
+            break;
+        }
+        if (run + 1) % 100 == 0 {
+            println!("\t{} inputs passed", run + 1);
+        }
+    }
+
+    match failed {
+        None => {
+            println!(
+                "{}",
+                format!("All {} inputs passed.", settings.runs)
+                    .green()
+                    .bold()
+            );
+            true
+        }
+        Some((seed, size, input, failure)) => {
+            println!(
+                "{}",
+                format!("Input with seed {} and size {} {}", seed, size, failure)
+                    .red()
+                    .bold()
+            );
+            println!("Shrinking...");
+            let (input, steps) = shrink(day, input, &failure, generator.shrink, settings.timeout);
+            println!(
+                "{}",
+                format!("Shrunk input ({} steps):", steps).yellow().bold()
+            );
+            println!("{}", input);
+            println!(
+                "Reproduce with: aoc24 {} fuzz --seed {} --runs 1 --max-size {}",
+                day, seed, settings.max_size
+            );
+            false
+        }
+    }
+}
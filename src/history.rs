@@ -0,0 +1,288 @@
+use crate::{params, variants};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The file runs are appended to, one JSON object per line.
+pub const HISTORY_PATH: &str = "./.aoc24_cache/history.jsonl";
+
+/// A single run of a day, as stored in the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub day: u8,
+    /// `1`, `2`, `b` or `extra:<name>`, like the `--part` argument.
+    pub part: String,
+    pub input_hash: String,
+    pub init_ns: u64,
+    pub precompute_ns: u64,
+    /// Missing if the part wasn't run.
+    pub one_ns: Option<u64>,
+    pub two_ns: Option<u64>,
+    /// A hash of the answer, so changes can be noticed without storing the answer itself.
+    pub one_hash: Option<String>,
+    pub two_hash: Option<String>,
+    /// The time and answer hash of an extra part, missing in runs of the official parts.
+    pub extra_ns: Option<u64>,
+    pub extra_hash: Option<String>,
+    /// `debug` or `release`.
+    pub profile: String,
+    pub commit: Option<String>,
+    pub cpu: Option<String>,
+    /// The variant selected with `--variant`, missing for the default one.
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// The parameters overridden with `--param`, as key and value.
+    #[serde(default)]
+    pub params: Vec<(String, String)>,
+}
+
+impl RunRecord {
+    /// Creates a record of a run finished now, filling in the environment of this process.
+    pub fn new(day: u8, part: &str, input: &str) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|v| v.as_secs())
+                .unwrap_or(0),
+            day,
+            part: part.to_string(),
+            input_hash: hash(input),
+            init_ns: 0,
+            precompute_ns: 0,
+            one_ns: None,
+            two_ns: None,
+            one_hash: None,
+            two_hash: None,
+            extra_ns: None,
+            extra_hash: None,
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+            .to_string(),
+            commit: git_commit(),
+            cpu: cpu_model(),
+            variant: variants::selected(),
+            params: params::overrides(),
+        }
+    }
+
+    /// Whether the timings of two runs can be compared, meaning they were made
+    /// with the same input, build profile and CPU, and ran the same part
+    /// with the same variant and parameters.
+    fn comparable(&self, other: &Self) -> bool {
+        self.day == other.day
+            && self.input_hash == other.input_hash
+            && self.profile == other.profile
+            && self.cpu == other.cpu
+            && self.part == other.part
+            && self.variant == other.variant
+            && self.params == other.params
+    }
+
+    fn phases(&self) -> [Option<u64>; 5] {
+        [
+            Some(self.init_ns),
+            Some(self.precompute_ns),
+            self.one_ns,
+            self.two_ns,
+            self.extra_ns,
+        ]
+    }
+}
+
+/// A stable FNV-1a hash, formatted as hex.
+pub fn hash(data: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in data.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn cpu_model() -> Option<String> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo
+        .lines()
+        .find(|v| v.starts_with("model name"))
+        .and_then(|v| v.split_once(':'))
+        .map(|(_, model)| model.trim().to_string())
+}
+
+/// Appends a run to the history file, creating it if needed.
+pub fn append(record: &RunRecord) -> std::io::Result<()> {
+    let path = Path::new(HISTORY_PATH);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)
+}
+
+/// Reads all runs of the history. Lines that can't be parsed are skipped.
+pub fn load() -> std::io::Result<Vec<RunRecord>> {
+    let file = match File::open(HISTORY_PATH) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(err),
+    };
+
+    let mut records = vec![];
+    for line in BufReader::new(file).lines() {
+        if let Ok(record) = serde_json::from_str(&line?) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Formats a unix timestamp as `YYYY-MM-DD HH:MM` in UTC.
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+
+    // Converts days since the epoch into a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + (m <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        y,
+        m,
+        d,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+/// Prints the runs of a day, oldest first, showing at most the last `limit`.
+///
+/// Phases taking more than `threshold` percent longer than the best comparable run are flagged,
+/// as are answers differing from the previous comparable run.
+/// Returns the number of flagged runs among the shown ones.
+pub fn print_report(records: &[RunRecord], day: u8, threshold: f64, limit: usize) -> usize {
+    let runs: Vec<&RunRecord> = records.iter().filter(|v| v.day == day).collect();
+    if runs.is_empty() {
+        println!("No runs of day {} were recorded yet.", day);
+        return 0;
+    }
+
+    let part_width = runs.iter().map(|v| v.part.len()).max().unwrap_or(0).max(4);
+    println!("{} Day {}", "History of".green().bold(), day);
+    println!(
+        "{}",
+        format!(
+            "{:16}  {:7}  {:9}  {:part_width$}  {:>11}  {:>11}  {:>11}  {:>11}  {:>11}",
+            "date",
+            "profile",
+            "commit",
+            "part",
+            "parsing",
+            "precompute",
+            "part 1",
+            "part 2",
+            "extra"
+        )
+        .bold()
+    );
+
+    let mut flagged = 0;
+    for (i, run) in runs
+        .iter()
+        .enumerate()
+        .skip(runs.len().saturating_sub(limit))
+    {
+        let comparable: Vec<&&RunRecord> = runs.iter().filter(|v| v.comparable(run)).collect();
+        let mut slower = false;
+
+        let phases = run.phases();
+        let cells: Vec<String> = (0..phases.len())
+            .map(|phase| {
+                let Some(ns) = phases[phase] else {
+                    return format!("{:>11}", "-");
+                };
+                let best = comparable
+                    .iter()
+                    .filter_map(|v| v.phases()[phase])
+                    .min()
+                    .unwrap_or(ns);
+                let cell = format!(
+                    "{:>11}",
+                    crate::dynamic_range_time_format(&Duration::from_nanos(ns))
+                );
+
+                let percent = (ns as f64 / best.max(1) as f64 - 1.0) * 100.0;
+                if ns > best && percent > threshold {
+                    slower = true;
+                    format!("{}", cell.red().bold())
+                } else if ns == best {
+                    format!("{}", cell.green())
+                } else {
+                    cell
+                }
+            })
+            .collect();
+
+        let previous = runs[..i].iter().rev().find(|v| v.comparable(run));
+        let answer_changed = previous.is_some_and(|previous| {
+            (run.one_hash.is_some()
+                && previous.one_hash.is_some()
+                && run.one_hash != previous.one_hash)
+                || (run.two_hash.is_some()
+                    && previous.two_hash.is_some()
+                    && run.two_hash != previous.two_hash)
+                || (run.extra_hash.is_some()
+                    && previous.extra_hash.is_some()
+                    && run.extra_hash != previous.extra_hash)
+        });
+
+        let mut notes = vec![];
+        if slower {
+            notes.push(format!("slower than best by more than {}%", threshold));
+        }
+        if answer_changed {
+            notes.push("answer changed".to_string());
+        }
+        if slower || answer_changed {
+            flagged += 1;
+        }
+
+        println!(
+            "{:16}  {:7}  {:9}  {:part_width$}  {}  {}",
+            format_timestamp(run.timestamp),
+            run.profile,
+            run.commit.as_deref().unwrap_or("-"),
+            run.part,
+            cells.join("  "),
+            notes.join(", ").yellow()
+        );
+    }
+
+    flagged
+}
@@ -0,0 +1,298 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub type Color = [u8; 3];
+
+/// The colors used when exporting images.
+///
+/// The first color is used as background, the others for set pixels.
+/// Grids with more kinds of tiles than colors reuse the non-background colors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette(Vec<Color>);
+
+impl Palette {
+    fn parse_color(color: &str) -> Result<Color, String> {
+        let hex = color.trim().trim_start_matches('#');
+        if hex.len() != 6 {
+            return Err(format!(
+                "\"{}\" is not a color in the #rrggbb format.",
+                color
+            ));
+        }
+
+        let mut rgb = [0; 3];
+        for (i, c) in rgb.iter_mut().enumerate() {
+            *c = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| format!("\"{}\" is not a color in the #rrggbb format.", color))?;
+        }
+        Ok(rgb)
+    }
+
+    fn get(&self, index: usize) -> Color {
+        if index == 0 || self.0.len() == 1 {
+            self.0[0]
+        } else {
+            self.0[1 + (index - 1) % (self.0.len() - 1)]
+        }
+    }
+}
+
+impl std::str::FromStr for Palette {
+    type Err = String;
+
+    /// Parses either the name of a built-in palette (`mono`, `inverted`, `aoc`),
+    /// or a comma separated list of `#rrggbb` colors.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let colors = match s {
+            "mono" => "#ffffff,#000000",
+            "inverted" => "#000000,#ffffff",
+            "aoc" => "#0f0f23,#ffff66,#00cc00,#cccccc,#ff0000,#9999cc",
+            _ => s,
+        };
+
+        let colors = colors
+            .split(',')
+            .map(Self::parse_color)
+            .collect::<Result<Vec<Color>, String>>()?;
+
+        if colors.len() < 2 {
+            return Err("A palette needs at least two colors.".to_string());
+        }
+
+        Ok(Self(colors))
+    }
+}
+
+/// Where and how to export images of answers.
+#[derive(Debug, Clone)]
+pub struct ImageExport {
+    pub path: PathBuf,
+    pub scale: u32,
+    pub palette: Palette,
+}
+
+impl ImageExport {
+    /// The path for one of the exported images, created by adding `name` to the file stem.
+    /// `out.png` becomes `out-part1.png` for example.
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|v| v.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let file_name = match self.path.extension() {
+            Some(ext) => format!("{}-{}.{}", stem, name, ext.to_string_lossy()),
+            None => format!("{}-{}", stem, name),
+        };
+        self.path.with_file_name(file_name)
+    }
+}
+
+/// An image made of palette indices.
+pub struct IndexedImage(Vec<Vec<usize>>);
+
+impl IndexedImage {
+    fn width(&self) -> usize {
+        self.0.iter().map(|row| row.len()).max().unwrap_or(0)
+    }
+
+    fn get(&self, x: usize, y: usize) -> usize {
+        self.0[y].get(x).copied().unwrap_or(0)
+    }
+
+    /// Calls `f` with the palette index of every pixel of the scaled image, row by row.
+    fn for_each_scaled(&self, scale: u32, mut f: impl FnMut(usize)) {
+        let scale = scale.max(1) as usize;
+        for y in 0..self.0.len() * scale {
+            for x in 0..self.width() * scale {
+                f(self.get(x / scale, y / scale));
+            }
+        }
+    }
+
+    fn write_pbm(&self, w: &mut impl Write, scale: u32) -> std::io::Result<()> {
+        let scale_u = scale.max(1) as usize;
+        writeln!(w, "P1")?;
+        writeln!(w, "{} {}", self.width() * scale_u, self.0.len() * scale_u)?;
+
+        let mut column = 0;
+        let mut result = Ok(());
+        self.for_each_scaled(scale, |index| {
+            column += 1;
+            let separator = if column % (self.width() * scale_u) == 0 {
+                "\n"
+            } else {
+                " "
+            };
+            if result.is_ok() {
+                result = write!(w, "{}{}", (index != 0) as u8, separator);
+            }
+        });
+        result
+    }
+
+    fn write_pgm(&self, w: &mut impl Write, scale: u32, palette: &Palette) -> std::io::Result<()> {
+        let scale_u = scale.max(1) as usize;
+        writeln!(w, "P2")?;
+        writeln!(w, "{} {}", self.width() * scale_u, self.0.len() * scale_u)?;
+        writeln!(w, "255")?;
+
+        let mut column = 0;
+        let mut result = Ok(());
+        self.for_each_scaled(scale, |index| {
+            let [r, g, b] = palette.get(index);
+            let gray = (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000;
+            column += 1;
+            let separator = if column % (self.width() * scale_u) == 0 {
+                "\n"
+            } else {
+                " "
+            };
+            if result.is_ok() {
+                result = write!(w, "{}{}", gray, separator);
+            }
+        });
+        result
+    }
+
+    fn write_png(&self, w: impl Write, scale: u32, palette: &Palette) -> std::io::Result<()> {
+        let scale_u = scale.max(1) as usize;
+        let mut encoder = png::Encoder::new(
+            w,
+            (self.width() * scale_u) as u32,
+            (self.0.len() * scale_u) as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut data = Vec::with_capacity(self.width() * self.0.len() * scale_u * scale_u * 3);
+        self.for_each_scaled(scale, |index| data.extend(palette.get(index)));
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&data))
+            .map_err(std::io::Error::other)
+    }
+
+    /// Writes the image, choosing the format from the file extension.
+    /// Supports `pbm`, `pgm` and `png`.
+    pub fn save(&self, path: &Path, scale: u32, palette: &Palette) -> std::io::Result<()> {
+        if self.0.is_empty() {
+            return Err(std::io::Error::other("the image is empty"));
+        }
+
+        let extension = path
+            .extension()
+            .map(|v| v.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !["pbm", "pgm", "png"].contains(&extension.as_str()) {
+            return Err(std::io::Error::other(format!(
+                "unsupported image format \"{}\", use pbm, pgm or png",
+                extension
+            )));
+        }
+
+        let mut file = BufWriter::new(File::create(path)?);
+        match extension.as_str() {
+            "pbm" => self.write_pbm(&mut file, scale)?,
+            "pgm" => self.write_pgm(&mut file, scale, palette)?,
+            _ => self.write_png(&mut file, scale, palette)?,
+        }
+
+        file.flush()
+    }
+}
+
+impl From<&[Vec<bool>]> for IndexedImage {
+    fn from(bm: &[Vec<bool>]) -> Self {
+        Self(
+            bm.iter()
+                .map(|row| row.iter().map(|v| *v as usize).collect())
+                .collect(),
+        )
+    }
+}
+
+impl From<&[Vec<char>]> for IndexedImage {
+    /// Assigns every kind of tile its own color, in the order they first appear.
+    /// `.` and spaces are considered background.
+    fn from(grid: &[Vec<char>]) -> Self {
+        let mut tiles: Vec<char> = vec![];
+
+        Self(
+            grid.iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|c| {
+                            if *c == '.' || *c == ' ' {
+                                return 0;
+                            }
+                            match tiles.iter().position(|v| v == c) {
+                                Some(i) => i + 1,
+                                None => {
+                                    tiles.push(*c);
+                                    tiles.len()
+                                }
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(
+        image: &IndexedImage,
+        write: impl FnOnce(&IndexedImage, &mut Vec<u8>) -> std::io::Result<()>,
+    ) -> String {
+        let mut out = vec![];
+        write(image, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn encodes_bitmap_as_pbm() {
+        let bm = vec![vec![true, false], vec![false, true, true]];
+        let image = IndexedImage::from(bm.as_slice());
+
+        assert_eq!(
+            encode(&image, |image, w| image.write_pbm(w, 1)),
+            "P1\n3 2\n1 0 0\n0 1 1\n"
+        );
+        assert_eq!(
+            encode(&image, |image, w| image.write_pbm(w, 2)),
+            "P1\n6 4\n1 1 0 0 0 0\n1 1 0 0 0 0\n0 0 1 1 1 1\n0 0 1 1 1 1\n"
+        );
+    }
+
+    #[test]
+    fn encodes_grid_as_pgm() {
+        let grid = vec![
+            "#.o".chars().collect::<Vec<char>>(),
+            " #o".chars().collect(),
+        ];
+        let image = IndexedImage::from(grid.as_slice());
+        let palette: Palette = "#000000,#ffffff,#ff0000".parse().unwrap();
+
+        assert_eq!(
+            encode(&image, |image, w| image.write_pgm(w, 1, &palette)),
+            "P2\n3 2\n255\n255 0 76\n0 255 76\n"
+        );
+    }
+
+    #[test]
+    fn reuses_palette_colors() {
+        let palette: Palette = "#000000,#111111,#222222".parse().unwrap();
+        assert_eq!(palette.get(0), [0x00; 3]);
+        assert_eq!(palette.get(2), [0x22; 3]);
+        assert_eq!(palette.get(3), [0x11; 3]);
+        assert!("#000000".parse::<Palette>().is_err());
+        assert!("#00000g,#ffffff".parse::<Palette>().is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use lazy_static::lazy_static;
+use mut_static::MutStatic;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+
+lazy_static! {
+    static ref EXAMPLE_OVERRIDE: MutStatic<Option<&'static str>> = MutStatic::from(None);
+    /// The normalized embedded examples, by the address and length of the embedded one.
+    static ref NORMALIZED_EXAMPLES: MutStatic<HashMap<(usize, usize), &'static str>> =
+        MutStatic::from(HashMap::new());
+    /// Every example that was overridden with, so that saving the same one again doesn't leak it again.
+    static ref OVERRIDES: MutStatic<HashSet<&'static str>> = MutStatic::from(HashSet::new());
+}
+
+/// The most non-ASCII characters reported by [`warn_non_ascii`].
+const MAX_NON_ASCII_WARNINGS: usize = 5;
+
+/// Brings an input into the shape the days expect:
+/// no byte order mark, `\n` line endings, no trailing whitespace on lines, and no trailing empty lines.
+///
+/// Borrows the input if nothing needs to change.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let input = input.trim_end();
+
+    let clean = !input.contains('\r')
+        && input
+            .split('\n')
+            .all(|line| line.len() == line.trim_end().len());
+    if clean {
+        return Cow::Borrowed(input);
+    }
+
+    let mut out = String::with_capacity(input.len());
+    // Lone `\r` are old Mac line endings, treat them like `\n` too.
+    for (i, line) in input
+        .split("\r\n")
+        .flat_map(|v| v.split(['\r', '\n']))
+        .enumerate()
+    {
+        if i > 0 {
+            out.push('\n');
+        }
+        out += line.trim_end();
+    }
+    Cow::Owned(out)
+}
+
+/// Normalizes an example input embedded in the binary.
+///
+/// Examples are usually clean already. If they aren't, for example because git converted them
+/// to CRLF line endings, the normalized copy is leaked once, as days can borrow from their input.
+pub fn normalize_example(input: &'static str) -> &'static str {
+    let key = (input.as_ptr() as usize, input.len());
+    if let Some(normalized) = NORMALIZED_EXAMPLES.read().unwrap().get(&key) {
+        return normalized;
+    }
+
+    let normalized = match normalize(input) {
+        Cow::Borrowed(input) => input,
+        Cow::Owned(input) => Box::leak(input.into_boxed_str()),
+    };
+    NORMALIZED_EXAMPLES.write().unwrap().insert(key, normalized);
+    normalized
+}
+
+/// Replaces the example of whichever day is tested next, `None` to use the embedded one again.
+/// Extra parts that bring their own example keep using it.
+///
+/// The example is leaked, as days can borrow from their input,
+/// but only once for every distinct example.
+pub fn override_example(example: Option<&str>) {
+    let example = example.map(|v| {
+        let example = normalize(v);
+        let mut overrides = OVERRIDES.write().unwrap();
+        match overrides.get(&*example) {
+            Some(leaked) => *leaked,
+            None => {
+                let leaked: &'static str = Box::leak(example.into_owned().into_boxed_str());
+                overrides.insert(leaked);
+                leaked
+            }
+        }
+    });
+    *EXAMPLE_OVERRIDE.write().unwrap() = example;
+}
+
+/// The example the days are tested with: the override if there is one,
+/// otherwise the normalized embedded example.
+pub fn example(embedded: &'static str) -> &'static str {
+    EXAMPLE_OVERRIDE
+        .read()
+        .unwrap()
+        .unwrap_or_else(|| normalize_example(embedded))
+}
+
+/// Logs a warning for the first few non-ASCII characters of an input,
+/// which most parsers don't expect and are often the result of copying the input from a browser.
+pub fn warn_non_ascii(input: &str) {
+    if input.is_ascii() {
+        return;
+    }
+
+    let mut found = input.lines().enumerate().flat_map(|(y, line)| {
+        line.chars()
+            .enumerate()
+            .filter(|(_, c)| !c.is_ascii())
+            .map(move |(x, c)| (y + 1, x + 1, c))
+    });
+    for (line, column, c) in found.by_ref().take(MAX_NON_ASCII_WARNINGS) {
+        warn!(
+            line,
+            column, "The input contains the non-ASCII character {:?}", c
+        );
+    }
+    let remaining = found.count();
+    if remaining > 0 {
+        warn!("The input contains {} more non-ASCII characters", remaining);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrows_clean_input() {
+        assert!(matches!(normalize("1 2\n3 4"), Cow::Borrowed("1 2\n3 4")));
+        assert!(matches!(
+            normalize("1 2\n3 4\n\n"),
+            Cow::Borrowed("1 2\n3 4")
+        ));
+    }
+
+    #[test]
+    fn converts_line_endings() {
+        assert_eq!(normalize("a\r\nb\r\n"), "a\nb");
+        assert_eq!(normalize("a\rb\r"), "a\nb");
+        assert_eq!(normalize("a\r\n\r\nb"), "a\n\nb");
+    }
+
+    #[test]
+    fn strips_byte_order_mark() {
+        assert_eq!(normalize("\u{feff}a\nb\n"), "a\nb");
+        assert_eq!(normalize("\u{feff}a\r\nb"), "a\nb");
+    }
+
+    #[test]
+    fn trims_trailing_whitespace() {
+        assert_eq!(normalize("a  \nb\t\n  c \n \n"), "a\nb\n  c");
+        assert_eq!(normalize("a \r\n\r\n"), "a");
+    }
+
+    #[test]
+    fn normalizes_examples_once() {
+        let example: &'static str = "x \r\ny\r\n";
+        let normalized = normalize_example(example);
+        assert_eq!(normalized, "x\ny");
+        assert!(std::ptr::eq(normalized, normalize_example(example)));
+    }
+}
@@ -1,98 +1,135 @@
+use crate::checkpoints::Checkpoint;
 use crate::days::Answer;
 use crate::days::Day;
 use crate::days::DayImpl;
+use crate::days::SolveError;
+use crate::days::utils::Map;
+use crate::history::RunRecord;
+use crate::image::IndexedImage;
 use aoc_macro::*;
+use clap::{App, AppSettings, ArgMatches};
 use colored::*;
-use lazy_static::lazy_static;
-use mut_static::MutStatic;
 use std::time::Duration;
 
+pub mod batch;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod checkpoints;
+pub mod crosscheck;
+pub mod dashboard;
 mod days;
+pub mod debugger;
+pub mod fuzz;
+pub mod history;
+mod image;
+pub mod input;
+pub mod logging;
+pub mod metamorphic;
+pub mod params;
+pub mod puzzle;
+pub mod report;
+pub mod scale;
+pub mod serve;
+mod tui;
+pub mod variants;
+pub mod visualizer;
+pub mod watch;
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum Verbosity {
-    None,
-    Verbose,
-    Development,
-}
+pub use image::{ImageExport, Palette};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Part {
     One,
     Two,
     Both,
+    /// A named extra part of the day, see `DayImpl::extra_parts`.
+    Extra(String),
 }
 
-#[derive(Debug, Clone)]
-pub struct Settings {
-    verbosity: Verbosity,
-}
-
-impl Settings {
-    fn set_verbosity(&mut self, new: Verbosity) {
-        self.verbosity = new;
-    }
-}
-
-lazy_static! {
-    static ref VERBOSITY: MutStatic<Settings> = MutStatic::from(Settings {
-        verbosity: Verbosity::None
-    });
-}
-
-pub fn set_verbosity(new_verbosity: Verbosity) {
-    let mut handle = VERBOSITY.write().unwrap();
-    handle.set_verbosity(new_verbosity);
-}
-
-pub fn get_verbosity() -> Verbosity {
-    VERBOSITY.read().unwrap().verbosity.clone()
-}
+#[doc(hidden)]
+pub use tracing;
 
+/// Logs a debug event, shown when running with `--verbose`.
 #[macro_export]
 macro_rules! vprintln {
     ($($arg:tt)*) => {
-        #[cfg(debug_assertions)]
-        if $crate::get_verbosity() ==  $crate::Verbosity::Verbose || $crate::get_verbosity() ==  $crate::Verbosity::Development {
-            println!(
-                $($arg)*
-            )
-        }
+        $crate::tracing::debug!($($arg)*)
     };
 }
 
+/// Prints part of a line when running with `--verbose`, so a line can be built in pieces.
+/// Unlike [`vprintln!`] this prints to stdout instead of logging an event.
 #[macro_export]
 macro_rules! vprint {
     ($($arg:tt)*) => {
-        #[cfg(debug_assertions)]
-        if $crate::get_verbosity() ==  $crate::Verbosity::Verbose || $crate::get_verbosity() ==  $crate::Verbosity::Development {
-            print!(
-                $($arg)*
-            )
+        if $crate::tracing::enabled!($crate::tracing::Level::DEBUG) {
+            print!($($arg)*)
         }
     };
 }
 
+/// Logs a trace event, shown when running with `--dev`.
 #[macro_export]
 macro_rules! dprintln {
     ($($arg:tt)*) => {
-        #[cfg(debug_assertions)]
-        if $crate::get_verbosity() == $crate::Verbosity::Development  {
-            println!(
-                $($arg)*
-            )
-        }
+        $crate::tracing::trace!($($arg)*)
     };
 }
 
+/// Prints part of a line when running with `--dev`, so a line can be built in pieces.
+/// Unlike [`dprintln!`] this prints to stdout instead of logging an event.
 #[macro_export]
 macro_rules! dprint {
     ($($arg:tt)*) => {
-        #[cfg(debug_assertions)]
-        if $crate::get_verbosity() == $crate::Verbosity::Development  {
-            print!(
-                $($arg)*
-            )
+        if $crate::tracing::enabled!($crate::tracing::Level::TRACE) {
+            print!($($arg)*)
+        }
+    };
+}
+
+/// Marks the end of a named section inside a part.
+/// `run_day` shows the time spent in each section below the part's time.
+#[macro_export]
+macro_rules! checkpoint {
+    ($label:literal) => {
+        $crate::checkpoints::checkpoint($label)
+    };
+}
+
+/// Pushes a frame to the visualizer.
+/// The grid and highlights are only evaluated if visualization is enabled.
+#[macro_export]
+macro_rules! visualize {
+    ($grid:expr, $highlights:expr) => {
+        if $crate::visualizer::is_enabled() {
+            $crate::visualizer::push_frame($crate::visualizer::Frame {
+                grid: $grid,
+                highlights: $highlights,
+            })
+        }
+    };
+}
+
+/// Records a step for the debugger, with an optional frame and named breakpoint conditions.
+/// Nothing is evaluated unless the debugger is recording.
+///
+/// `debug_step!(state, frame: my_frame, break "name" if condition)`
+#[macro_export]
+macro_rules! debug_step {
+    ($state:expr $(, frame: $frame:expr)? $(, break $name:literal if $cond:expr)*) => {
+        if $crate::debugger::is_recording() {
+            #[allow(unused_mut)]
+            let mut step = $crate::debugger::Step {
+                state: format!("{:#?}", $state),
+                ..Default::default()
+            };
+            $(step.frame = Some($frame);)?
+            $(
+                if $cond {
+                    step.breakpoints.push($name);
+                }
+            )*
+            $crate::debugger::record(step);
         }
     };
 }
@@ -124,138 +161,381 @@ fn dynamic_range_time_format(d: &Duration) -> String {
     }
 }
 
-pub fn run_day(day: u8, part: Part, input: &str) {
-    println!("{} Day {}", "Starting".green().bold(), day);
-    println!("{}", "-----------------------".green().bold());
-    let (one, two, init_t, one_t, two_t) = match part {
-        Part::Both => match_and_run_day_both!(),
+fn print_checkpoints(checkpoints: &[Checkpoint]) {
+    let width = checkpoints.iter().map(|v| v.label.len()).max().unwrap_or(0);
+    for checkpoint in checkpoints {
+        println!(
+            "\t\t  {:width$}  {}{}",
+            format!("{}:", checkpoint.label),
+            dynamic_range_time_format(&checkpoint.total).blue(),
+            if checkpoint.count > 1 {
+                format!(" ({}×)", checkpoint.count)
+            } else {
+                String::new()
+            },
+            width = width + 1
+        );
+    }
+}
+
+fn format_solution(solution: &Result<Answer, SolveError>) -> ColoredString {
+    match solution {
+        Ok(answer) => format!("{}", answer).bold().blue(),
+        Err(SolveError::Unimplemented) => format!("{}", SolveError::Unimplemented).bold().yellow(),
+        Err(err) => format!("{}", err).bold().red(),
+    }
+}
+
+fn hash_solution(solution: &Result<Answer, SolveError>) -> String {
+    match solution {
+        Ok(answer) => history::hash(&answer.to_string()),
+        Err(err) => history::hash(&format!("error: {}", err)),
+    }
+}
+
+/// Exports every answer that is a bitmap or grid, as well as the map exposed by the day.
+fn export_images(
+    export: &ImageExport,
+    answers: &[(&str, &Result<Answer, SolveError>)],
+    map: Option<Map<char>>,
+) {
+    let images = answers
+        .iter()
+        .filter_map(|(name, answer)| match answer {
+            Ok(Answer::Bitmap(bm)) => Some((*name, IndexedImage::from(bm.as_slice()))),
+            Ok(Answer::Grid(grid)) => Some((*name, IndexedImage::from(grid.as_slice()))),
+            _ => None,
+        })
+        .chain(map.map(|map| ("map", IndexedImage::from(map.get_raw_tiles().as_slice()))));
+
+    for (name, image) in images {
+        let path = export.path_for(name);
+        match image.save(&path, export.scale, &export.palette) {
+            Ok(_) => println!("{} {}", "Exported image to".green(), path.display()),
+            Err(err) => println!(
+                "{}",
+                format!("Couldn't export image to {}: {}", path.display(), err)
+                    .red()
+                    .bold()
+            ),
+        }
+    }
+}
+
+/// The answers and timings of a single run of a day.
+/// Answers of parts that weren't run are `Ok(Answer::Number(0))`.
+struct DayRun {
+    part: Part,
+    one: Result<Answer, SolveError>,
+    two: Result<Answer, SolveError>,
+    extra: Result<Answer, SolveError>,
+    init_t: Duration,
+    pre_t: Duration,
+    one_t: Duration,
+    two_t: Duration,
+    extra_t: Duration,
+    /// The map exposed by the day, if it was requested.
+    map: Option<Map<char>>,
+}
+
+impl DayRun {
+    fn runs_one(&self) -> bool {
+        self.part == Part::Both || self.part == Part::One
+    }
+
+    fn runs_two(&self) -> bool {
+        self.part == Part::Both || self.part == Part::Two
+    }
+
+    /// The name of the extra part, if one was run.
+    fn extra_name(&self) -> Option<&str> {
+        match &self.part {
+            Part::Extra(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The names and times of the parts that were run, in the order they are printed.
+    fn part_times(&self) -> Vec<(String, Duration)> {
+        let mut times = vec![];
+        if self.runs_one() {
+            times.push(("Part 1".to_string(), self.one_t));
+        }
+        if self.runs_two() {
+            times.push(("Part 2".to_string(), self.two_t));
+        }
+        if let Some(name) = self.extra_name() {
+            times.push((format!("Extra {}", name), self.extra_t));
+        }
+        times
+    }
+
+    /// The record of this run for the history.
+    fn record(&self, day: u8, input: &str) -> RunRecord {
+        let mut record = RunRecord::new(
+            day,
+            &match &self.part {
+                Part::One => "1".to_string(),
+                Part::Two => "2".to_string(),
+                Part::Both => "b".to_string(),
+                Part::Extra(name) => format!("extra:{}", name),
+            },
+            input,
+        );
+        record.init_ns = self.init_t.as_nanos() as u64;
+        record.precompute_ns = self.pre_t.as_nanos() as u64;
+        if self.runs_one() {
+            record.one_ns = Some(self.one_t.as_nanos() as u64);
+            record.one_hash = Some(hash_solution(&self.one));
+        }
+        if self.runs_two() {
+            record.two_ns = Some(self.two_t.as_nanos() as u64);
+            record.two_hash = Some(hash_solution(&self.two));
+        }
+        if self.extra_name().is_some() {
+            record.extra_ns = Some(self.extra_t.as_nanos() as u64);
+            record.extra_hash = Some(hash_solution(&self.extra));
+        }
+        record
+    }
+}
+
+/// Runs a day on an already normalized input, without printing anything.
+fn solve_day(day: u8, part: Part, input: &str) -> DayRun {
+    solve_day_with_map(day, part, input, false)
+}
+
+/// Like [`solve_day`], also taking the map exposed by the day if `map` is set.
+fn solve_day_with_map(day: u8, part: Part, input: &str, map: bool) -> DayRun {
+    let mut run = DayRun {
+        part: part.clone(),
+        one: Ok(Answer::Number(0)),
+        two: Ok(Answer::Number(0)),
+        extra: Ok(Answer::Number(0)),
+        init_t: Duration::ZERO,
+        pre_t: Duration::ZERO,
+        one_t: Duration::ZERO,
+        two_t: Duration::ZERO,
+        extra_t: Duration::ZERO,
+        map: None,
+    };
+
+    match &part {
+        Part::Both => {
+            (
+                run.one, run.two, run.init_t, run.pre_t, run.one_t, run.two_t, run.map,
+            ) = match_and_run_day_both!()
+        }
         Part::One => {
-            let (one, init_t, one_t) = match_and_run_day_one!();
-            (one, Answer::Number(0), init_t, one_t, Duration::ZERO)
+            (run.one, run.init_t, run.pre_t, run.one_t, run.map) = match_and_run_day_one!()
         }
         Part::Two => {
-            let (two, init_t, two_t) = match_and_run_day_two!();
-            (Answer::Number(0), two, init_t, Duration::ZERO, two_t)
+            (run.two, run.init_t, run.pre_t, run.two_t, run.map) = match_and_run_day_two!()
         }
-    };
+        Part::Extra(name) => {
+            (run.extra, run.init_t, run.pre_t, run.extra_t, run.map) = match_and_run_day_extra!()
+        }
+    }
+
+    run
+}
+
+/// The names of a day's extra parts, which can be selected with [`Part::Extra`].
+pub fn extra_part_names(day: u8) -> Vec<&'static str> {
+    match_day_extra_part_names!()
+}
+
+/// The arguments of a day's tools, as one subcommand per tool, or `None` if the day has no tools.
+pub fn tool_app(day: u8) -> Option<App<'static, 'static>> {
+    let commands = match_day_tool_commands!();
+    if commands.is_empty() {
+        return None;
+    }
+    Some(
+        App::new("tool")
+            .bin_name(format!("aoc24 {} tool", day))
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .global_setting(AppSettings::DisableVersion)
+            .subcommands(commands),
+    )
+}
+
+/// Runs the tool selected in `matches`, which were parsed with [`tool_app`],
+/// on the input or the example if there is none.
+pub fn run_tool(day: u8, input: Option<&str>, matches: &ArgMatches) -> Result<String, String> {
+    let input = input.map(input::normalize);
+    let input = input.as_deref();
+    match_and_run_day_tool!()
+}
+
+/// The answers of the parts that ran, by name.
+type Answers = Vec<(&'static str, Result<Answer, SolveError>)>;
+
+/// Runs a day and prints the results, returning the record of the run for the history.
+pub fn run_day(day: u8, part: Part, input: &str, export: Option<&ImageExport>) -> RunRecord {
+    run_day_answers(day, part, input, export).0
+}
+
+/// Like [`run_day`], also returning the answers of the parts that ran.
+fn run_day_answers(
+    day: u8,
+    part: Part,
+    input: &str,
+    export: Option<&ImageExport>,
+) -> (RunRecord, Answers) {
+    let _span = tracing::info_span!("day", day, mode = "run").entered();
+    let input = &*input::normalize(input);
+    input::warn_non_ascii(input);
+    println!("{} Day {}", "Starting".green().bold(), day);
+    println!("{}", "-----------------------".green().bold());
+    let mut run = solve_day_with_map(day, part.clone(), input, export.is_some());
 
     println!("{}:", "Results".green().bold());
     println!(
         "\t{}: {}",
         "Parsing time".green(),
-        dynamic_range_time_format(&init_t).bold().blue()
+        dynamic_range_time_format(&run.init_t).bold().blue()
     );
-    if part == Part::Both || part == Part::One {
-        println!("\t{}:", "Part 1".green());
-        println!("\t\tSolution: {}", format!("{}", one).bold().blue());
-        println!(
-            "\t\tTook:     {}",
-            if cfg!(debug_assertions) {
-                (dynamic_range_time_format(&one_t) + " (DEBUG)")
-                    .bold()
-                    .red()
-            } else {
-                dynamic_range_time_format(&one_t).bold().blue()
-            }
-        );
+    println!(
+        "\t{}: {}",
+        "Precompute time".green(),
+        dynamic_range_time_format(&run.pre_t).bold().blue()
+    );
+    if run.runs_one() {
+        print_part_result("Part 1", &run.one, &run.one_t, "one");
     }
-    if part == Part::Both || part == Part::Two {
-        println!("\t{}:", "Part 2".green());
-        println!("\t\tSolution: {}", format!("{}", two).bold().blue());
-        println!(
-            "\t\tTook:     {}",
-            if cfg!(debug_assertions) {
-                (dynamic_range_time_format(&two_t) + " (DEBUG)")
-                    .bold()
-                    .red()
-            } else {
-                dynamic_range_time_format(&two_t).bold().blue()
-            }
+    if run.runs_two() {
+        print_part_result("Part 2", &run.two, &run.two_t, "two");
+    }
+    if let Some(name) = run.extra_name() {
+        print_part_result(
+            &format!("Extra {}", name),
+            &run.extra,
+            &run.extra_t,
+            "extra",
         );
     }
+
+    if let Some(export) = export {
+        let answers = match run.extra_name() {
+            Some(_) => vec![("extra", &run.extra)],
+            None => vec![("part1", &run.one), ("part2", &run.two)],
+        };
+        export_images(export, &answers, run.map.take());
+    }
+
+    let record = run.record(day, input);
+    let (runs_one, runs_two, runs_extra) =
+        (run.runs_one(), run.runs_two(), run.extra_name().is_some());
+    let mut answers = vec![];
+    if runs_one {
+        answers.push(("part1", run.one));
+    }
+    if runs_two {
+        answers.push(("part2", run.two));
+    }
+    if runs_extra {
+        answers.push(("extra", run.extra));
+    }
+    (record, answers)
+}
+
+/// Prints the solution and time of a part, followed by the time spent in its checkpoints.
+fn print_part_result(
+    name: &str,
+    solution: &Result<Answer, SolveError>,
+    time: &Duration,
+    checkpoints: &'static str,
+) {
+    println!("\t{}:", name.green());
+    println!("\t\tSolution: {}", format_solution(solution));
+    println!(
+        "\t\tTook:     {}",
+        if cfg!(debug_assertions) {
+            (dynamic_range_time_format(time) + " (DEBUG)").bold().red()
+        } else {
+            dynamic_range_time_format(time).bold().blue()
+        }
+    );
+    print_checkpoints(&checkpoints::take(checkpoints));
+}
+
+fn print_test_result(
+    name: &str,
+    passed: bool,
+    result: &Result<Answer, SolveError>,
+    expected: &Answer,
+) {
+    println!(
+        "\t{}: {}",
+        name.green(),
+        match (passed, result) {
+            (true, _) => "PASSED".green().bold(),
+            (false, Err(SolveError::Unimplemented)) => "UNIMPLEMENTED".yellow().bold(),
+            (false, Err(_)) => "ERROR".red().bold(),
+            (false, Ok(_)) => "FAILED".red().bold(),
+        }
+    );
+    println!("\t\tResult:   {}", format_solution(result));
+    println!("\t\tExpected: {}", format!("{}", expected).bold().blue());
+}
+
+pub fn test_day(day: u8, part: Part, export: Option<&ImageExport>) -> bool {
+    test_day_answers(day, part, export).0
 }
 
-pub fn test_day(day: u8, part: Part) -> bool {
+/// Like [`test_day`], also returning the answers of the parts that ran.
+fn test_day_answers(day: u8, part: Part, export: Option<&ImageExport>) -> (bool, Answers) {
+    let _span = tracing::info_span!("day", day, mode = "test").entered();
     println!("{} Day {}", "Testing".green().bold(), day);
     println!("{}", "-----------------------".green().bold());
-    match part {
+    let (passed, answers) = match part {
         Part::Both => {
             let ((one_p, one_r, one_e), (two_p, two_r, two_e)) = match_and_test_day_both!();
 
             println!("{}:", "Results".green().bold());
-            println!(
-                "\t{}: {}",
-                "Part 1".green(),
-                match one_p {
-                    true => {
-                        "PASSED".green().bold()
-                    }
-                    false => {
-                        "FAILED".red().bold()
-                    }
-                }
-            );
-            println!("\t\tResult:   {}", format!("{}", one_r).bold().blue());
-            println!("\t\tExpected: {}", format!("{}", one_e).bold().blue());
-
-            println!(
-                "\t{}: {}",
-                "Part 2".green(),
-                match two_p {
-                    true => {
-                        "PASSED".green().bold()
-                    }
-                    false => {
-                        "FAILED".red().bold()
-                    }
-                }
-            );
-            println!("\t\tResult:   {}", format!("{}", two_r).bold().blue());
-            println!("\t\tExpected: {}", format!("{}", two_e).bold().blue());
+            print_test_result("Part 1", one_p, &one_r, &one_e);
+            print_test_result("Part 2", two_p, &two_r, &two_e);
 
-            !(!two_p || !one_p)
+            (one_p && two_p, vec![("part1", one_r), ("part2", two_r)])
         }
         Part::One => {
             let (one_p, one_r, one_e) = match_and_test_day_one!();
 
             println!("{}:", "Results".green().bold());
-            println!(
-                "\t{}: {}",
-                "Part 1".green(),
-                match one_p {
-                    true => {
-                        "PASSED".green().bold()
-                    }
-                    false => {
-                        "FAILED".red().bold()
-                    }
-                }
-            );
-            println!("\t\tResult:   {}", format!("{}", one_r).bold().blue());
-            println!("\t\tExpected: {}", format!("{}", one_e).bold().blue());
+            print_test_result("Part 1", one_p, &one_r, &one_e);
 
-            !one_p
+            (one_p, vec![("part1", one_r)])
         }
         Part::Two => {
             let (two_p, two_r, two_e) = match_and_test_day_two!();
 
             println!("{}:", "Results".green().bold());
-            println!(
-                "\t{}: {}",
-                "Part 2".green(),
-                match two_p {
-                    true => {
-                        "PASSED".green().bold()
-                    }
-                    false => {
-                        "FAILED".red().bold()
-                    }
-                }
-            );
-            println!("\t\tResult:   {}", format!("{}", two_r).bold().blue());
-            println!("\t\tExpected: {}", format!("{}", two_e).bold().blue());
+            print_test_result("Part 2", two_p, &two_r, &two_e);
 
-            !two_p
+            (two_p, vec![("part2", two_r)])
         }
+        Part::Extra(name) => {
+            let name = name.as_str();
+            let (extra_p, extra_r, extra_e) = match_and_test_day_extra!();
+
+            println!("{}:", "Results".green().bold());
+            print_test_result(&format!("Extra {}", name), extra_p, &extra_r, &extra_e);
+
+            (extra_p, vec![("extra", extra_r)])
+        }
+    };
+
+    if let Some(export) = export {
+        export_images(
+            export,
+            &answers
+                .iter()
+                .map(|(name, answer)| (*name, answer))
+                .collect::<Vec<_>>(),
+            match_and_render_test_day_map!(),
+        );
     }
+
+    (passed, answers)
 }
@@ -0,0 +1,55 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogSettings {
+    /// The least severe events shown, besides those selected by the filter.
+    pub level: Level,
+    /// Additional filter directives, using the syntax of `RUST_LOG`.
+    /// Events of a single day can be selected using `[day{day=6}]=trace`,
+    /// modules using `aoc24::days::d06=trace`.
+    pub filter: Option<String>,
+    /// Write to this file instead of stderr.
+    pub file: Option<PathBuf>,
+    pub format: LogFormat,
+}
+
+/// Installs the global subscriber, printing the events and phase spans allowed by the settings.
+pub fn init(settings: LogSettings) -> Result<(), String> {
+    let level = settings.level.as_str().to_lowercase();
+    let directives = match (&settings.filter, std::env::var("RUST_LOG")) {
+        (Some(filter), _) => format!("{},{}", level, filter),
+        (None, Ok(filter)) => format!("{},{}", level, filter),
+        (None, Err(_)) => level,
+    };
+    let filter = EnvFilter::try_new(&directives).map_err(|err| err.to_string())?;
+
+    let writer = match &settings.file {
+        Some(path) => BoxMakeWriter::new(Mutex::new(
+            File::create(path).map_err(|err| err.to_string())?,
+        )),
+        None => BoxMakeWriter::new(std::io::stderr),
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .with_span_events(FmtSpan::CLOSE);
+
+    match settings.format {
+        LogFormat::Text => builder.with_ansi(settings.file.is_none()).try_init(),
+        LogFormat::Json => builder.json().try_init(),
+    }
+    .map_err(|err| err.to_string())
+}
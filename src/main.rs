@@ -1,5 +1,14 @@
-use aoc24::{run_day, set_verbosity, test_day, Part, Verbosity};
-use clap::{App, AppSettings, Arg, SubCommand};
+use aoc24::crosscheck::{self, CrosscheckSettings};
+use aoc24::fuzz::{self, FuzzSettings};
+use aoc24::history::{self, RunRecord};
+use aoc24::logging::{self, LogFormat, LogSettings};
+use aoc24::scale::{self, ScaleSettings};
+use aoc24::serve::{self, ServeSettings};
+use aoc24::{
+    ImageExport, Palette, Part, batch, dashboard, debugger, extra_part_names, metamorphic, params,
+    puzzle, report, run_day, run_tool, test_day, tool_app, variants, visualizer, watch,
+};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use colored::*;
 use reqwest::blocking::Client;
 use reqwest::cookie::Jar;
@@ -8,6 +17,8 @@ use std::env;
 use std::fs;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::Level;
 
 // NOTE: Since this CLI was coded using clap in december of 2021, there seem to
 // have been huge breaking changes, requiring me to rewrite the CLI completely
@@ -23,8 +34,7 @@ fn main() {
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .arg(
             Arg::with_name("day")
-                .help("The number of the day to execute")
-                .required(true)
+                .help("The number of the day to execute. Required by every subcommand except report.")
                 .takes_value(true)
                 .validator(|v| match v.parse::<u8>() {
                     Ok(day) => {
@@ -39,12 +49,16 @@ fn main() {
         )
         .arg(
             Arg::with_name("part")
-                .help("Specifies the part of the day to compute.")
+                .help("Specifies the part of the day to compute: 1, 2, b for both, or extra:<name> for an extra part of the day.")
                 .long("part")
                 .short("p")
                 .default_value("b")
-                .possible_values(&["1", "2", "b"])
-                .takes_value(true),
+                .takes_value(true)
+                .validator(|v| match v.as_str() {
+                    "1" | "2" | "b" => Ok(()),
+                    _ if v.strip_prefix("extra:").is_some_and(|v| !v.is_empty()) => Ok(()),
+                    _ => Err("The part must be 1, 2, b or extra:<name>.".to_string()),
+                }),
         )
         .arg(
             Arg::with_name("verbose")
@@ -58,8 +72,86 @@ fn main() {
                 .long("dev")
                 .short("d")
                 .conflicts_with("verbose"))
+        .arg(
+            Arg::with_name("log_file")
+                .help("Write log output to a file instead of stderr.")
+                .long("log-file")
+                .takes_value(true)
+                .value_name("path"))
+        .arg(
+            Arg::with_name("log_format")
+                .help("The format of log output.")
+                .long("log-format")
+                .takes_value(true)
+                .default_value("text")
+                .possible_values(&["text", "json"]))
+        .arg(
+            Arg::with_name("log_filter")
+                .help("Additional log filter directives in the RUST_LOG syntax, e.g. \"aoc24::days::d06=trace\" to select a module or \"[day{day=6}]=trace\" to select a day.")
+                .long("log-filter")
+                .takes_value(true)
+                .value_name("directives"))
+        .arg(
+            Arg::with_name("visualize")
+                .help("Show the visualization of days that support it. Timings include the time spent drawing.")
+                .long("visualize"))
+        .arg(
+            Arg::with_name("fps")
+                .help("The frames per second of the visualization.")
+                .long("fps")
+                .takes_value(true)
+                .default_value("10")
+                .validator(|v| match v.parse::<u32>() {
+                    Ok(fps) if fps > 0 => Ok(()),
+                    _ => Err("The FPS must be a positive number.".to_string()),
+                }))
+        .arg(
+            Arg::with_name("cast")
+                .help("Write the visualization to an asciinema .cast file instead of the terminal.")
+                .long("cast")
+                .takes_value(true)
+                .value_name("path")
+                .requires("visualize"))
+        .arg(
+            Arg::with_name("debugger")
+                .help("Record the steps of days that support it and browse them in an interactive debugger afterwards.")
+                .long("debugger"))
+        .arg(
+            Arg::with_name("max_steps")
+                .help("The maximum number of steps recorded for the debugger.")
+                .long("max-steps")
+                .takes_value(true)
+                .default_value("100000")
+                .validator(|v| match v.parse::<usize>() {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err("The maximum number of steps must be a number.".to_string()),
+                }))
+        .arg(
+            Arg::with_name("variant")
+                .help("Use the named variant of the parts that have one, instead of the default implementation.")
+                .long("variant")
+                .takes_value(true)
+                .value_name("name"))
+        .arg(
+            Arg::with_name("param")
+                .help("Override a parameter of the day, like a grid size that differs between the example and the real input. Can be given multiple times.")
+                .long("param")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("key=value")
+                .validator(|v| match v.split_once('=') {
+                    Some((key, _)) if !key.is_empty() => Ok(()),
+                    _ => Err("Parameters must be given as key=value.".to_string()),
+                }))
         .subcommand(
-            SubCommand::with_name("test").about("Test the day with the example input data."),
+            SubCommand::with_name("test")
+                .about("Test the day with the example input data.")
+                .arg(Arg::with_name("watch")
+                    .help("Retest every time the example in src/days/test_inputs is saved, reading it instead of the embedded one.")
+                    .short("w")
+                    .long("watch"))
+                .args(&image_export_args()),
         )
         .subcommand(
             SubCommand::with_name("auto")
@@ -72,7 +164,11 @@ fn main() {
                 .arg(Arg::with_name("no_cache")
                     .help("Don't cache the input, and delete any current cache for this day.")
                     .short("N")
-                    .long("no-cache")))
+                    .long("no-cache"))
+                .arg(Arg::with_name("refresh_puzzle")
+                    .help("Download the puzzle page again even if it is cached, to store the answers accepted since.")
+                    .long("refresh-puzzle"))
+                .args(&image_export_args()))
         .subcommand(
             SubCommand::with_name("run")
                 .about("Use either a file or stdin as input and run the solution.")
@@ -82,10 +178,269 @@ fn main() {
                     .long("file")
                     .takes_value(true)
             )
+                .arg(Arg::with_name("inputs")
+                    .help("Run the day over every file in a directory. Answers are checked against a file with the same name and the extension .expected, holding one answer per line, if it exists.")
+                    .long("inputs")
+                    .takes_value(true)
+                    .value_name("dir")
+                    .conflicts_with("file"))
+                .arg(Arg::with_name("watch")
+                    .help("Rerun every time the input file is saved.")
+                    .short("w")
+                    .long("watch")
+                    .requires("file"))
+                .args(&image_export_args())
         )
+        .subcommand(
+            SubCommand::with_name("history")
+                .about("Show the recorded runs of the day and flag performance regressions.")
+                .arg(Arg::with_name("threshold")
+                    .help("Flag runs taking this many percent longer than the best comparable run.")
+                    .short("t")
+                    .long("threshold")
+                    .takes_value(true)
+                    .default_value("10")
+                    .validator(|v| match v.parse::<f64>() {
+                        Ok(threshold) if threshold >= 0.0 => Ok(()),
+                        _ => Err("The threshold must be a non-negative number.".to_string()),
+                    }))
+                .arg(Arg::with_name("limit")
+                    .help("The number of most recent runs to show.")
+                    .short("n")
+                    .long("limit")
+                    .takes_value(true)
+                    .default_value("20")
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The limit must be a number.".to_string()),
+                    })))
+        .subcommand(
+            SubCommand::with_name("tool")
+                .about("Run one of the day's tools on its parsed input, like a rule checker. Uses the cached input of the auto command if it exists, otherwise the example. `tool help` lists the tools of the day.")
+                .setting(AppSettings::TrailingVarArg)
+                .arg(Arg::with_name("file")
+                    .help("Use this input file instead.")
+                    .short("f")
+                    .long("file")
+                    .takes_value(true))
+                .arg(Arg::with_name("example")
+                    .help("Use the example, even if there is a cached input.")
+                    .long("example")
+                    .conflicts_with("file"))
+                .arg(Arg::with_name("command")
+                    .help("The tool and its arguments.")
+                    .multiple(true)
+                    .required(true)))
+        .subcommand(
+            SubCommand::with_name("crosscheck")
+                .about("Run all variants of the day on the example, real inputs and generated inputs, reporting disagreements and comparing their timings. The cached input of the auto command is included if it exists.")
+                .arg(Arg::with_name("file")
+                    .help("An additional input file.")
+                    .short("f")
+                    .long("file")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1))
+                .arg(Arg::with_name("inputs")
+                    .help("Also use every input in this directory.")
+                    .long("inputs")
+                    .takes_value(true)
+                    .value_name("dir"))
+                .arg(Arg::with_name("generated")
+                    .help("The number of inputs taken from the day's input generator, if it has one.")
+                    .long("generated")
+                    .takes_value(true)
+                    .default_value("100")
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The number of generated inputs must be a number.".to_string()),
+                    }))
+                .arg(Arg::with_name("seed")
+                    .help("The seed of the first generated input. Random if not provided.")
+                    .long("seed")
+                    .takes_value(true)
+                    .validator(|v| match v.parse::<u64>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The seed must be a number.".to_string()),
+                    }))
+                .arg(Arg::with_name("max_size")
+                    .help("The largest size passed to the generator.")
+                    .long("max-size")
+                    .takes_value(true)
+                    .default_value("20")
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(size) if size > 0 => Ok(()),
+                        _ => Err("The maximum size must be a positive number.".to_string()),
+                    })))
+        .subcommand(
+            SubCommand::with_name("fuzz")
+                .about("Run the day on random inputs from its input generator, reporting the smallest input that panics, times out or is rejected.")
+                .arg(Arg::with_name("runs")
+                    .help("The number of inputs to generate.")
+                    .long("runs")
+                    .takes_value(true)
+                    .default_value("1000")
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The number of runs must be a number.".to_string()),
+                    }))
+                .arg(Arg::with_name("seed")
+                    .help("The seed of the first input, the others use the following seeds. Random if not provided.")
+                    .long("seed")
+                    .takes_value(true)
+                    .validator(|v| match v.parse::<u64>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The seed must be a number.".to_string()),
+                    }))
+                .arg(Arg::with_name("max_size")
+                    .help("The largest size passed to the generator, like the number of lines or the width of a map.")
+                    .long("max-size")
+                    .takes_value(true)
+                    .default_value("20")
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(size) if size > 0 => Ok(()),
+                        _ => Err("The maximum size must be a positive number.".to_string()),
+                    }))
+                .arg(Arg::with_name("timeout")
+                    .help("The time in milliseconds after which a run counts as hanging.")
+                    .long("timeout")
+                    .takes_value(true)
+                    .default_value("1000")
+                    .validator(|v| match v.parse::<u64>() {
+                        Ok(timeout) if timeout > 0 => Ok(()),
+                        _ => Err("The timeout must be a positive number.".to_string()),
+                    })))
+        .subcommand(
+            SubCommand::with_name("metamorphic")
+                .about("Check that the transformations the day declares, like rotating its map, don't change the answers on the example and real inputs. The cached input of the auto command is included if it exists.")
+                .arg(Arg::with_name("file")
+                    .help("An additional input file.")
+                    .short("f")
+                    .long("file")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1))
+                .arg(Arg::with_name("seed")
+                    .help("The seed used to shuffle lines. Random if not provided.")
+                    .long("seed")
+                    .takes_value(true)
+                    .validator(|v| match v.parse::<u64>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The seed must be a number.".to_string()),
+                    })))
+        .subcommand(
+            SubCommand::with_name("scale")
+                .about("Time the day on generated inputs of growing size and estimate the complexity of every phase of the selected part from how its time grows with the length of the input.")
+                .arg(Arg::with_name("start")
+                    .help("The size passed to the generator for the smallest input.")
+                    .long("start")
+                    .takes_value(true)
+                    .default_value("10")
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(size) if size > 0 => Ok(()),
+                        _ => Err("The start size must be a positive number.".to_string()),
+                    }))
+                .arg(Arg::with_name("factor")
+                    .help("The factor by which the size grows with every step.")
+                    .long("factor")
+                    .takes_value(true)
+                    .default_value("2")
+                    .validator(|v| match v.parse::<f64>() {
+                        Ok(factor) if factor > 1.0 => Ok(()),
+                        _ => Err("The factor must be a number larger than 1.".to_string()),
+                    }))
+                .arg(Arg::with_name("steps")
+                    .help("The number of sizes to try.")
+                    .long("steps")
+                    .takes_value(true)
+                    .default_value("8")
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The number of steps must be a number.".to_string()),
+                    }))
+                .arg(Arg::with_name("repeats")
+                    .help("The number of runs per size, of which the fastest one counts.")
+                    .long("repeats")
+                    .takes_value(true)
+                    .default_value("3")
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(repeats) if repeats > 0 => Ok(()),
+                        _ => Err("The number of repeats must be a positive number.".to_string()),
+                    }))
+                .arg(Arg::with_name("seed")
+                    .help("The seed of the generated inputs. Random if not provided.")
+                    .long("seed")
+                    .takes_value(true)
+                    .validator(|v| match v.parse::<u64>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The seed must be a number.".to_string()),
+                    }))
+                .arg(Arg::with_name("max_time")
+                    .help("The time in milliseconds after which no larger inputs are tried.")
+                    .long("max-time")
+                    .takes_value(true)
+                    .default_value("2000")
+                    .validator(|v| match v.parse::<u64>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The maximum time must be a number.".to_string()),
+                    })))
+        .subcommand(
+            SubCommand::with_name("report")
+                .about("Test all days and update the progress badges and table of days in the README.")
+                .arg(Arg::with_name("readme")
+                    .help("The README to update, between the <!-- report:badges --> and <!-- report:days --> markers.")
+                    .long("readme")
+                    .takes_value(true)
+                    .value_name("path")
+                    .default_value("README.md"))
+                .arg(Arg::with_name("no_readme")
+                    .help("Only print the table of days, don't update the README.")
+                    .long("no-readme"))
+                .arg(Arg::with_name("html")
+                    .help("Also write the table of days to a standalone HTML page.")
+                    .long("html")
+                    .takes_value(true)
+                    .value_name("path")))
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Serve the solutions over HTTP on localhost. GET /days lists the implemented days, POST /days/{n}?part=1|2|both solves the input in the body and returns the answers and timings as JSON.")
+                .arg(Arg::with_name("port")
+                    .help("The port to listen on.")
+                    .long("port")
+                    .takes_value(true)
+                    .default_value("8024")
+                    .validator(|v| match v.parse::<u16>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The port must be a number up to 65535.".to_string()),
+                    }))
+                .arg(Arg::with_name("max_body")
+                    .help("The largest accepted input, in bytes.")
+                    .long("max-body")
+                    .takes_value(true)
+                    .default_value("1048576")
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The maximum input size must be a number.".to_string()),
+                    }))
+                .arg(Arg::with_name("timeout")
+                    .help("The time in milliseconds after which a request is answered with an error. The run keeps going in the background and other requests to solve are rejected until it ends.")
+                    .long("timeout")
+                    .takes_value(true)
+                    .default_value("10000")
+                    .validator(|v| match v.parse::<u64>() {
+                        Ok(timeout) if timeout > 0 => Ok(()),
+                        _ => Err("The timeout must be a positive number.".to_string()),
+                    })))
+        .subcommand(
+            SubCommand::with_name("dashboard")
+                .about("Open a full-screen dashboard of all days, to run, test and benchmark them and read their answers and cached puzzles."))
+        .subcommand(
+            SubCommand::with_name(fuzz::CHECK_COMMAND)
+                .about("Run both parts on the input from stdin for the fuzz command.")
+                .setting(AppSettings::Hidden))
         .get_matches();
 
-    if cfg!(debug_assertions) {
+    if cfg!(debug_assertions) && matches.subcommand_name() != Some(fuzz::CHECK_COMMAND) {
         println!(
             "{}",
             "This binary was built in debug mode. To improve performance, please add --release to the build command."
@@ -94,32 +449,149 @@ fn main() {
         );
     }
 
-    let day = matches
-        .value_of("day")
-        .unwrap()
-        .parse::<u8>()
-        .expect("Failed to parse day argument.");
+    let level = if matches.args.contains_key("development") {
+        Level::TRACE
+    } else if matches.args.contains_key("verbose") {
+        Level::DEBUG
+    } else {
+        Level::WARN
+    };
+
+    if let Err(err) = logging::init(LogSettings {
+        level,
+        filter: matches.value_of("log_filter").map(|v| v.to_owned()),
+        file: matches.value_of("log_file").map(|v| v.into()),
+        format: match matches.value_of("log_format") {
+            Some("json") => LogFormat::Json,
+            _ => LogFormat::Text,
+        },
+    }) {
+        panic!("Error while setting up logging: {}", err);
+    }
+
+    if let ("report", Some(c_matches)) = matches.subcommand() {
+        report(c_matches);
+        return;
+    }
+
+    if let ("serve", Some(c_matches)) = matches.subcommand() {
+        let settings = ServeSettings {
+            port: c_matches.value_of("port").unwrap().parse().unwrap(),
+            max_body: c_matches.value_of("max_body").unwrap().parse().unwrap(),
+            timeout: Duration::from_millis(c_matches.value_of("timeout").unwrap().parse().unwrap()),
+        };
+        if let Err(err) = serve::serve(&settings) {
+            println!("{}", format!("Server error: {}", err).red().bold());
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let ("dashboard", _) = matches.subcommand() {
+        if let Err(err) = dashboard::open() {
+            println!("{}", format!("Dashboard error: {}", err).red().bold());
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let day = match matches.value_of("day") {
+        Some(day) => day.parse::<u8>().expect("Failed to parse day argument."),
+        None => {
+            println!(
+                "{}",
+                "The day is required for this subcommand.".red().bold()
+            );
+            std::process::exit(1);
+        }
+    };
 
     let part: Part = match matches.value_of("part") {
         Some("1") => Part::One,
         Some("2") => Part::Two,
         Some("b") => Part::Both,
+        Some(v) if v.starts_with("extra:") => Part::Extra(v["extra:".len()..].to_owned()),
         _ => panic!("unexpected part argument."),
     };
 
-    if matches.args.contains_key("verbose") {
-        #[cfg(not(debug_assertions))]
-        println!("{}", "verbosity and development prints are only available in unoptimized builds (compiled without --release).".red().bold());
-        set_verbosity(Verbosity::Verbose);
+    if matches.args.contains_key("visualize") {
+        let output = match matches.value_of("cast") {
+            Some(path) => visualizer::Output::Cast(path.into()),
+            None => visualizer::Output::Terminal,
+        };
+        let fps = matches.value_of("fps").unwrap().parse().unwrap();
+        visualizer::enable(output, fps);
+    }
+
+    variants::select(matches.value_of("variant").map(|v| v.to_owned()));
+
+    let overrides = matches
+        .values_of("param")
+        .map(|values| {
+            values
+                .map(|v| v.split_once('=').unwrap())
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Err(err) = params::select(day, overrides) {
+        println!("{}", err.red().bold());
+        std::process::exit(1);
     }
 
-    if matches.args.contains_key("development") {
-        #[cfg(not(debug_assertions))]
-        println!("{}", "verbosity and development prints are only available in unoptimized builds (compiled without --release).".red().bold());
-        set_verbosity(Verbosity::Development);
+    if let Part::Extra(name) = &part {
+        let names = extra_part_names(day);
+        if !names.contains(&name.as_str()) {
+            let message = if names.is_empty() {
+                format!("Day {} has no extra parts.", day)
+            } else {
+                format!(
+                    "Day {} has no extra part named \"{}\", use one of {}.",
+                    day,
+                    name,
+                    names.join(", ")
+                )
+            };
+            println!("{}", message.red().bold());
+            std::process::exit(1);
+        }
+    }
+
+    if matches.args.contains_key("debugger") {
+        debugger::enable(matches.value_of("max_steps").unwrap().parse().unwrap());
     }
 
     match matches.subcommand() {
+        ("run", Some(c_matches)) if c_matches.is_present("inputs") => {
+            if let Part::Extra(_) = part {
+                println!("{}", "Extra parts can't be run on a directory of inputs, as those only have expected answers for parts 1 and 2.".red().bold());
+                std::process::exit(1);
+            }
+            let dir = c_matches.value_of("inputs").unwrap();
+            let inputs = batch::load(Path::new(dir))
+                .unwrap_or_else(|err| panic!("Error while reading the inputs in {}: {}", dir, err));
+            let (passed, records) = batch::run(day, part, &inputs);
+            for record in records {
+                record_run(record);
+            }
+            if !passed {
+                std::process::exit(1);
+            }
+        }
+        ("run", Some(c_matches)) if c_matches.is_present("watch") => {
+            let path = Path::new(c_matches.value_of("file").unwrap());
+            if let Err(err) =
+                watch::watch_run(day, part, path, get_image_export(Some(c_matches)).as_ref())
+            {
+                println!(
+                    "{}",
+                    format!("Error while watching {}: {}", path.display(), err)
+                        .red()
+                        .bold()
+                );
+                std::process::exit(1);
+            }
+        }
         ("run", c_matches) => {
             let input = match c_matches {
                 Some(c_matches) => {
@@ -131,7 +603,12 @@ fn main() {
                 }
                 None => get_stdin_day_input(day),
             };
-            run_day(day, part, &input);
+            record_run(run_day(
+                day,
+                part,
+                &input,
+                get_image_export(c_matches).as_ref(),
+            ));
         }
         ("auto", c_matches) => {
             let session: Option<String> = match c_matches {
@@ -147,16 +624,278 @@ fn main() {
                 true
             };
 
-            let input = get_auto_input(day, session.as_ref(), cache);
-            run_day(day, part, &input);
+            let refresh_puzzle = c_matches.is_some_and(|v| v.is_present("refresh_puzzle"));
+
+            let input = get_auto_input(day, session.as_ref(), cache, refresh_puzzle);
+            record_run(run_day(
+                day,
+                part,
+                &input,
+                get_image_export(c_matches).as_ref(),
+            ));
+        }
+        ("test", Some(c_matches)) if c_matches.is_present("watch") => {
+            if let Err(err) =
+                watch::watch_test(day, part, get_image_export(Some(c_matches)).as_ref())
+            {
+                println!(
+                    "{}",
+                    format!("Error while watching the example: {}", err)
+                        .red()
+                        .bold()
+                );
+                std::process::exit(1);
+            }
         }
-        ("test", _) => {
-            if !test_day(day, part) {
+        ("test", c_matches) => {
+            if !test_day(day, part, get_image_export(c_matches).as_ref()) {
+                let _ = visualizer::finish();
+                if let Err(err) = debugger::browse() {
+                    println!("{}", format!("Debugger error: {}", err).red().bold());
+                }
                 std::process::exit(1);
             }
         }
+        ("crosscheck", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let mut inputs = vec![];
+            let cache_path = format!("./.aoc24_cache/input{:02}.txt", day);
+            if let Ok(input) = fs::read_to_string(&cache_path) {
+                inputs.push((cache_path, input));
+            }
+            for path in c_matches.values_of("file").into_iter().flatten() {
+                let input = fs::read_to_string(path)
+                    .unwrap_or_else(|err| panic!("Error while reading {}: {}", path, err));
+                inputs.push((path.to_owned(), input));
+            }
+            if let Some(dir) = c_matches.value_of("inputs") {
+                let batch = batch::load(Path::new(dir)).unwrap_or_else(|err| {
+                    panic!("Error while reading the inputs in {}: {}", dir, err)
+                });
+                inputs.extend(
+                    batch
+                        .into_iter()
+                        .map(|v| (v.path.display().to_string(), v.input)),
+                );
+            }
+
+            let settings = CrosscheckSettings {
+                inputs,
+                generated: c_matches.value_of("generated").unwrap().parse().unwrap(),
+                seed: match c_matches.value_of("seed") {
+                    Some(seed) => seed.parse().unwrap(),
+                    None => random_seed(),
+                },
+                max_size: c_matches.value_of("max_size").unwrap().parse().unwrap(),
+            };
+            if !crosscheck::crosscheck(day, &settings) {
+                std::process::exit(1);
+            }
+        }
+        (fuzz::CHECK_COMMAND, _) => fuzz::check_stdin(day),
+        ("fuzz", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let settings = FuzzSettings {
+                runs: c_matches.value_of("runs").unwrap().parse().unwrap(),
+                seed: match c_matches.value_of("seed") {
+                    Some(seed) => seed.parse().unwrap(),
+                    None => random_seed(),
+                },
+                max_size: c_matches.value_of("max_size").unwrap().parse().unwrap(),
+                timeout: Duration::from_millis(
+                    c_matches.value_of("timeout").unwrap().parse().unwrap(),
+                ),
+            };
+            if !fuzz::fuzz(day, &settings) {
+                std::process::exit(1);
+            }
+        }
+        ("metamorphic", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let mut inputs = vec![];
+            let cache_path = format!("./.aoc24_cache/input{:02}.txt", day);
+            if let Ok(input) = fs::read_to_string(&cache_path) {
+                inputs.push((cache_path, input));
+            }
+            for path in c_matches.values_of("file").into_iter().flatten() {
+                let input = fs::read_to_string(path)
+                    .unwrap_or_else(|err| panic!("Error while reading {}: {}", path, err));
+                inputs.push((path.to_owned(), input));
+            }
+
+            let seed = match c_matches.value_of("seed") {
+                Some(seed) => seed.parse().unwrap(),
+                None => random_seed(),
+            };
+            if !metamorphic::check(day, &inputs, seed) {
+                std::process::exit(1);
+            }
+        }
+        ("scale", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let settings = ScaleSettings {
+                start: c_matches.value_of("start").unwrap().parse().unwrap(),
+                factor: c_matches.value_of("factor").unwrap().parse().unwrap(),
+                steps: c_matches.value_of("steps").unwrap().parse().unwrap(),
+                repeats: c_matches.value_of("repeats").unwrap().parse().unwrap(),
+                seed: match c_matches.value_of("seed") {
+                    Some(seed) => seed.parse().unwrap(),
+                    None => random_seed(),
+                },
+                max_time: Duration::from_millis(
+                    c_matches.value_of("max_time").unwrap().parse().unwrap(),
+                ),
+            };
+            if !scale::scale(day, part, &settings) {
+                std::process::exit(1);
+            }
+        }
+        ("history", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let threshold = c_matches.value_of("threshold").unwrap().parse().unwrap();
+            let limit = c_matches.value_of("limit").unwrap().parse().unwrap();
+            match history::load() {
+                Ok(records) => {
+                    history::print_report(&records, day, threshold, limit);
+                }
+                Err(err) => panic!("Error while reading the run history: {}", err),
+            }
+        }
+        ("tool", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let Some(app) = tool_app(day) else {
+                println!("{}", format!("Day {} has no tools.", day).red().bold());
+                std::process::exit(1);
+            };
+            let tool_matches = app.get_matches_from(
+                std::iter::once("tool").chain(c_matches.values_of("command").unwrap()),
+            );
+
+            let cache_path = format!("./.aoc24_cache/input{:02}.txt", day);
+            let input = match c_matches.value_of("file") {
+                Some(path) => Some(
+                    fs::read_to_string(path)
+                        .unwrap_or_else(|err| panic!("Error while reading {}: {}", path, err)),
+                ),
+                None if c_matches.is_present("example") => None,
+                None => fs::read_to_string(&cache_path).ok(),
+            };
+            if input.is_none() {
+                println!("{}", "Using the example.".yellow());
+            }
+
+            match run_tool(day, input.as_deref(), &tool_matches) {
+                Ok(output) => println!("{}", output),
+                Err(err) => {
+                    println!("{}", err.red().bold());
+                    std::process::exit(1);
+                }
+            }
+        }
         _ => panic!("Unexpected Subcommand."),
     }
+
+    if let Err(err) = debugger::browse() {
+        println!("{}", format!("Debugger error: {}", err).red().bold());
+    }
+
+    if let Some(path) = matches.value_of("cast") {
+        match visualizer::finish() {
+            Ok(0) => println!("No frames were visualized, so {} wasn't written", path),
+            Ok(frames) => println!("Wrote {} frames to {}", frames, path),
+            Err(err) => println!(
+                "{}",
+                format!("Couldn't write {}: {}", path, err).red().bold()
+            ),
+        }
+    }
+}
+
+/// A seed for generated inputs, taken from the current time.
+fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|v| v.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn report(c_matches: &ArgMatches) {
+    let records = history::load().unwrap_or_else(|err| {
+        println!("Warning! couldn't read the run history! {:?}", err);
+        vec![]
+    });
+    let days = report::collect(&records);
+
+    println!("{}", report::markdown_table(&days));
+
+    if let Some(path) = c_matches.value_of("html") {
+        match fs::write(path, report::html(&days)) {
+            Ok(_) => println!("{} {}", "Wrote report to".green(), path),
+            Err(err) => println!(
+                "{}",
+                format!("Couldn't write {}: {}", path, err).red().bold()
+            ),
+        }
+    }
+
+    if !c_matches.args.contains_key("no_readme") {
+        let path = c_matches.value_of("readme").unwrap();
+        let result = fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|readme| report::update_readme(&readme, &days))
+            .and_then(|readme| fs::write(path, readme).map_err(|err| err.to_string()));
+        match result {
+            Ok(_) => println!("{} {}", "Updated".green(), path),
+            Err(err) => println!(
+                "{}",
+                format!("Couldn't update {}: {}", path, err).red().bold()
+            ),
+        }
+    }
+}
+
+/// Appends a run to the history, unless it was slowed down by the visualizer or the debugger.
+fn record_run(record: RunRecord) {
+    if visualizer::is_enabled() || debugger::is_enabled() {
+        return;
+    }
+    if let Err(err) = history::append(&record) {
+        println!("Warning! couldn't save the run history! {:?}", err);
+    }
+}
+
+fn image_export_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("export_image")
+            .help("Export bitmap and grid answers, as well as maps exposed by the day, as images. The format is chosen by the extension: pbm, pgm or png.")
+            .long("export-image")
+            .takes_value(true)
+            .value_name("path"),
+        Arg::with_name("scale")
+            .help("The size of a single pixel in exported images.")
+            .long("scale")
+            .takes_value(true)
+            .default_value("1")
+            .validator(|v| match v.parse::<u32>() {
+                Ok(scale) if scale > 0 => Ok(()),
+                _ => Err("The scale must be a positive number.".to_string()),
+            }),
+        Arg::with_name("palette")
+            .help("The colors of exported images: mono, inverted, aoc or a comma separated list of #rrggbb colors, starting with the background.")
+            .long("palette")
+            .takes_value(true)
+            .default_value("mono")
+            .validator(|v| v.parse::<Palette>().map(|_| ())),
+    ]
+}
+
+fn get_image_export(c_matches: Option<&ArgMatches>) -> Option<ImageExport> {
+    let c_matches = c_matches?;
+    Some(ImageExport {
+        path: c_matches.value_of("export_image")?.into(),
+        scale: c_matches.value_of("scale")?.parse().ok()?,
+        palette: c_matches.value_of("palette")?.parse().ok()?,
+    })
 }
 
 fn get_stdin_day_input(day: u8) -> String {
@@ -188,7 +927,38 @@ fn get_stdin_day_input(day: u8) -> String {
 
 fn download_input(day: u8, session: &String) -> Result<String, reqwest::Error> {
     println!("Downloading input for day {}", day);
+    download(
+        &format!("https://adventofcode.com/2024/day/{}/input", day),
+        session,
+    )
+}
+
+/// Downloads the puzzle description and caches it as text, for the dashboard.
+/// Failing to do so only prints a warning, as the input matters more.
+fn cache_puzzle(day: u8, session: &String) {
+    println!("Downloading puzzle for day {}", day);
+    let result = download(
+        &format!("https://adventofcode.com/2024/day/{}", day),
+        session,
+    )
+    .map_err(|err| err.to_string())
+    .and_then(|html| {
+        let path = puzzle::cache_path(day);
+        let _ = fs::create_dir(Path::new("./.aoc24_cache"));
+        fs::write(&path, puzzle::html_to_text(&html)).map_err(|err| err.to_string())?;
+        match puzzle::store_verified_answers(day, &html) {
+            Ok(0) => {}
+            Ok(count) => println!("Stored {} verified answer(s)", count),
+            Err(err) => println!("Warning! couldn't store the verified answers!{:?}", err),
+        }
+        Ok(())
+    });
+    if let Err(err) = result {
+        println!("Warning! couldn't save puzzle cache!{:?}", err);
+    }
+}
 
+fn download(url: &str, session: &String) -> Result<String, reqwest::Error> {
     let cookie_jar = Jar::default();
     cookie_jar.add_cookie_str(
         format!("session={}; Domain=adventofcode.com", session).as_ref(),
@@ -200,7 +970,7 @@ fn download_input(day: u8, session: &String) -> Result<String, reqwest::Error> {
         .build()?;
 
     let response = client
-        .get(format!("https://adventofcode.com/2024/day/{}/input", day))
+        .get(url)
         .header(
             USER_AGENT,
             "https://github.com/LeMoonStar/AoC24 aoc24@unitcore.de",
@@ -214,7 +984,13 @@ fn download_input(day: u8, session: &String) -> Result<String, reqwest::Error> {
     response.text()
 }
 
-fn get_auto_input(day: u8, session: Option<&String>, cache: bool) -> String {
+fn get_auto_input(day: u8, session: Option<&String>, cache: bool, refresh_puzzle: bool) -> String {
+    if let Some(session) = session
+        && (refresh_puzzle || !puzzle::cache_path(day).exists())
+    {
+        cache_puzzle(day, session);
+    }
+
     let cache_str = &format!("./.aoc24_cache/input{:02}.txt", day);
     let cache_path: &Path = Path::new(cache_str);
     match cache {
@@ -238,7 +1014,9 @@ fn get_auto_input(day: u8, session: Option<&String>, cache: bool) -> String {
                         }
                     }
                 } else {
-                    panic!("Neither a session argument nor the AOC_SESSION environment variable were provided, and there is no cache of the day's input.");
+                    panic!(
+                        "Neither a session argument nor the AOC_SESSION environment variable were provided, and there is no cache of the day's input."
+                    );
                 }
             }
         },
@@ -252,7 +1030,9 @@ fn get_auto_input(day: u8, session: Option<&String>, cache: bool) -> String {
                     }
                 }
             } else {
-                panic!("Neither a session argument nor the AOC_SESSION environment variable were provided, and there is no cache of the day's input.");
+                panic!(
+                    "Neither a session argument nor the AOC_SESSION environment variable were provided, and there is no cache of the day's input."
+                );
             }
         }
     }
@@ -0,0 +1,223 @@
+use crate::days::{Answer, SolveError, metamorphic_relations};
+use crate::fuzz::Rng;
+use crate::{Part, format_solution, input, params, solve_day};
+use colored::*;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A change of the input that some days' answers don't depend on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    /// Rotates the grid clockwise.
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrors the grid left to right.
+    FlipHorizontal,
+    /// Mirrors the grid top to bottom.
+    FlipVertical,
+    /// Mirrors the grid along the diagonal from the top left.
+    Transpose,
+    /// Reorders the lines randomly.
+    ShuffleLines,
+}
+
+impl Transform {
+    /// The position in the original grid that ends up at `(x, y)` of the transformed grid.
+    fn source(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Self::Rotate90 => (y, height - 1 - x),
+            Self::Rotate180 => (width - 1 - x, height - 1 - y),
+            Self::Rotate270 => (width - 1 - y, x),
+            Self::FlipHorizontal => (width - 1 - x, y),
+            Self::FlipVertical => (x, height - 1 - y),
+            Self::Transpose | Self::ShuffleLines => (y, x),
+        }
+    }
+
+    fn swaps_axes(&self) -> bool {
+        matches!(self, Self::Rotate90 | Self::Rotate270 | Self::Transpose)
+    }
+
+    /// Turns a direction arrow the same way the grid is turned.
+    fn turn_arrow(&self, c: char) -> char {
+        const CLOCKWISE: [char; 4] = ['^', '>', 'v', '<'];
+        let Some(i) = CLOCKWISE.iter().position(|v| *v == c) else {
+            return c;
+        };
+        match self {
+            Self::Rotate90 => CLOCKWISE[(i + 1) % 4],
+            Self::Rotate180 => CLOCKWISE[(i + 2) % 4],
+            Self::Rotate270 => CLOCKWISE[(i + 3) % 4],
+            Self::FlipHorizontal => ['^', '<', 'v', '>'][i],
+            Self::FlipVertical => ['v', '>', '^', '<'][i],
+            Self::Transpose => ['<', 'v', '>', '^'][i],
+            Self::ShuffleLines => c,
+        }
+    }
+
+    /// Applies the transformation to an input. With `arrows`, `^>v<` are treated as directions
+    /// and turned along with the grid.
+    ///
+    /// Returns `None` if the input isn't a rectangular grid but the transformation needs one.
+    pub fn apply(&self, input: &str, arrows: bool, rng: &mut Rng) -> Option<String> {
+        let lines: Vec<&str> = input.lines().collect();
+
+        if *self == Self::ShuffleLines {
+            let mut lines = lines;
+            for i in (1..lines.len()).rev() {
+                lines.swap(i, rng.below(i as u64 + 1) as usize);
+            }
+            return Some(lines.join("\n"));
+        }
+
+        let grid: Vec<Vec<char>> = lines.iter().map(|v| v.chars().collect()).collect();
+        let (width, height) = (grid.first()?.len(), grid.len());
+        if grid.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        let (new_width, new_height) = if self.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        Some(
+            (0..new_height)
+                .map(|y| {
+                    (0..new_width)
+                        .map(|x| {
+                            let (sx, sy) = self.source(x, y, width, height);
+                            let c = grid[sy][sx];
+                            if arrows { self.turn_arrow(c) } else { c }
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+}
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Rotate90 => write!(f, "rotate 90°"),
+            Self::Rotate180 => write!(f, "rotate 180°"),
+            Self::Rotate270 => write!(f, "rotate 270°"),
+            Self::FlipHorizontal => write!(f, "flip horizontally"),
+            Self::FlipVertical => write!(f, "flip vertically"),
+            Self::Transpose => write!(f, "transpose"),
+            Self::ShuffleLines => write!(f, "shuffle lines"),
+        }
+    }
+}
+
+type Results = [Result<Answer, SolveError>; 2];
+
+/// Runs both parts, or returns `None` if the day panicked.
+fn solve(day: u8, input: &str) -> Option<Results> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let run = solve_day(day, Part::Both, input);
+        [run.one, run.two]
+    }))
+    .ok()
+}
+
+/// Checks that the transformations a day declares don't change its answers,
+/// on the example and the given inputs.
+///
+/// Returns whether all answers stayed the same.
+pub fn check(day: u8, inputs: &[(String, String)], seed: u64) -> bool {
+    let Some(relations) = metamorphic_relations(day) else {
+        println!(
+            "{}",
+            format!("Day {} declares no metamorphic relations.", day)
+                .red()
+                .bold()
+        );
+        return false;
+    };
+    let _span = tracing::info_span!("day", day, mode = "metamorphic").entered();
+
+    println!(
+        "{} Day {} under {}, seed {}",
+        "Checking".green().bold(),
+        day,
+        relations
+            .transforms
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        seed
+    );
+    println!("{}", "-----------------------".green().bold());
+
+    // Panics are reported below instead of being printed where they happen.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut passed = true;
+    let sources = [("example".to_string(), relations.example.to_string(), true)]
+        .into_iter()
+        .chain(
+            inputs
+                .iter()
+                .map(|(name, input)| (name.clone(), input.clone(), false)),
+        );
+    for (name, source, is_example) in sources {
+        // The example and its transformations are parsed with the example's parameters.
+        let solve = |input: &str| match is_example {
+            true => params::with_example(|| solve(day, input)),
+            false => solve(day, input),
+        };
+        let source = input::normalize(&source).into_owned();
+        println!("{}:", name.green());
+        let Some(original) = solve(&source) else {
+            println!("\t{}", "The original input panicked.".red().bold());
+            passed = false;
+            continue;
+        };
+
+        let mut rng = Rng::new(seed);
+        for transform in relations.transforms {
+            let Some(transformed) = transform.apply(&source, relations.arrows, &mut rng) else {
+                println!(
+                    "\t{}: {}",
+                    transform,
+                    "SKIPPED (not a rectangular grid)".yellow().bold()
+                );
+                continue;
+            };
+
+            let Some(results) = solve(&transformed) else {
+                println!("\t{}: {}", transform, "PANICKED".red().bold());
+                passed = false;
+                continue;
+            };
+
+            let mismatches: Vec<usize> = (0..2)
+                .filter(|i| {
+                    original[*i] != Err(SolveError::Unimplemented) && results[*i] != original[*i]
+                })
+                .collect();
+            if mismatches.is_empty() {
+                println!("\t{}: {}", transform, "PASSED".green().bold());
+            } else {
+                passed = false;
+                println!("\t{}: {}", transform, "FAILED".red().bold());
+                for i in mismatches {
+                    println!(
+                        "\t\tPart {}: {} instead of {}",
+                        i + 1,
+                        format_solution(&results[i]),
+                        format_solution(&original[i])
+                    );
+                }
+            }
+        }
+    }
+
+    panic::set_hook(default_hook);
+    passed
+}
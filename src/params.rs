@@ -0,0 +1,63 @@
+use crate::days;
+use lazy_static::lazy_static;
+use mut_static::MutStatic;
+use std::cell::Cell;
+
+lazy_static! {
+    static ref OVERRIDES: MutStatic<Vec<(String, String)>> = MutStatic::from(vec![]);
+}
+
+/// Where the input being parsed comes from, which decides the defaults of the parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Real,
+    Example,
+    /// Made by an [`InputGenerator`](crate::days::InputGenerator).
+    Generated,
+}
+
+thread_local! {
+    /// Where the input being parsed on this thread comes from.
+    static SOURCE: Cell<Source> = const { Cell::new(Source::Real) };
+}
+
+/// Overrides parameters of a day for all following runs and tests,
+/// failing if the day doesn't have a parameter or the value doesn't fit it.
+pub fn select(day: u8, overrides: Vec<(String, String)>) -> Result<(), String> {
+    days::check_parameters(day, &overrides)?;
+    *OVERRIDES.write().unwrap() = overrides;
+    Ok(())
+}
+
+/// The parameters overridden with `--param`, by name.
+pub fn overrides() -> Vec<(String, String)> {
+    OVERRIDES.read().unwrap().clone()
+}
+
+/// Where the input being parsed comes from, so days use the matching parameters.
+pub fn source() -> Source {
+    SOURCE.get()
+}
+
+/// Calls `f`, which parses the example, with the example's parameters.
+pub fn with_example<R>(f: impl FnOnce() -> R) -> R {
+    with_source(Source::Example, f)
+}
+
+/// Calls `f`, which parses generated inputs, with the parameters of generated inputs.
+pub fn with_generated<R>(f: impl FnOnce() -> R) -> R {
+    with_source(Source::Generated, f)
+}
+
+fn with_source<R>(source: Source, f: impl FnOnce() -> R) -> R {
+    /// Resets the source even if parsing panics, as tests catch panics and continue.
+    struct Reset(Source);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            SOURCE.set(self.0);
+        }
+    }
+
+    let _reset = Reset(SOURCE.replace(source));
+    f()
+}
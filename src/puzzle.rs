@@ -0,0 +1,117 @@
+use crate::batch::{self, EXPECTED_EXTENSION};
+use std::fs;
+use std::path::PathBuf;
+
+/// Where the text of a day's puzzle is cached by the `auto` command.
+pub fn cache_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("./.aoc24_cache/puzzle{:02}.txt", day))
+}
+
+/// The cached text of a day's puzzle, if it was downloaded.
+pub fn load(day: u8) -> Option<String> {
+    fs::read_to_string(cache_path(day)).ok()
+}
+
+/// Where the answers accepted by Advent of Code are stored, next to the cached input,
+/// in the format of the batch mode's expected answers.
+pub fn answers_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("./.aoc24_cache/input{:02}.txt", day)).with_extension(EXPECTED_EXTENSION)
+}
+
+/// The stored answers of both parts that Advent of Code accepted.
+pub fn verified_answers(day: u8) -> [Option<String>; 2] {
+    match fs::read_to_string(answers_path(day)) {
+        Ok(answers) => batch::parse_expected(&answers),
+        Err(_) => [None, None],
+    }
+}
+
+/// Stores the accepted answers found on a puzzle page, keeping stored ones the page doesn't show.
+///
+/// Returns the number of answers found.
+pub fn store_verified_answers(day: u8, html: &str) -> std::io::Result<usize> {
+    let found = answers(html);
+    let count = found.iter().flatten().count();
+    if count == 0 {
+        return Ok(0);
+    }
+
+    let [one, two] = verified_answers(day);
+    let [new_one, new_two] = found;
+    let lines = [new_one.or(one), new_two.or(two)].map(|v| v.unwrap_or_else(|| "?".to_string()));
+    fs::write(answers_path(day), lines.join("\n") + "\n")?;
+    Ok(count)
+}
+
+/// The answers of a puzzle page, which shows them once a part is solved.
+fn answers(html: &str) -> [Option<String>; 2] {
+    const PREFIX: &str = "Your puzzle answer was <code>";
+
+    let mut found = html.match_indices(PREFIX).filter_map(|(start, _)| {
+        let rest = &html[start + PREFIX.len()..];
+        rest.find("</code>").map(|end| unescape(&rest[..end]))
+    });
+    [found.next(), found.next()]
+}
+
+/// Turns the HTML of a puzzle page into plain text, keeping only the puzzle descriptions.
+///
+/// This only handles the few tags the puzzle pages use: paragraphs, lists and code blocks
+/// become separate lines, every other tag is dropped.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+
+    let mut rest = html;
+    while let Some(start) = rest.find("<article") {
+        let article = &rest[start..];
+        let end = article.find("</article>").unwrap_or(article.len());
+        text += &article_to_text(&article[..end]);
+        text += "\n";
+        rest = &article[end..];
+    }
+
+    text.trim_end().to_string()
+}
+
+fn article_to_text(article: &str) -> String {
+    let mut text = String::new();
+
+    let mut rest = article;
+    while let Some(start) = rest.find('<') {
+        text += &unescape(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        let name = tag
+            .trim_start_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        match (tag.starts_with('/'), name) {
+            (false, "pre") => text += "\n",
+            (false, "li") => text += "\n  - ",
+            (true, "p" | "h2" | "ul") => text += "\n\n",
+            _ => {}
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text += &unescape(rest);
+
+    // Closing and opening tags both add line breaks, so runs of empty lines are collapsed.
+    let mut lines: Vec<&str> = vec![];
+    for line in text.lines().map(|v| v.trim_end()) {
+        if !(line.is_empty() && lines.last().is_none_or(|v| v.is_empty())) {
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
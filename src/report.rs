@@ -0,0 +1,298 @@
+use crate::days::{Day, DayImpl, SolveError};
+use crate::dynamic_range_time_format;
+use crate::history::RunRecord;
+use crate::puzzle;
+use aoc_macro::match_and_test_day_both;
+use std::time::Duration;
+
+/// The puzzle titles, indexed by day - 1.
+pub const TITLES: [&str; 25] = [
+    "Historian Hysteria",
+    "Red-Nosed Reports",
+    "Mull It Over",
+    "Ceres Search",
+    "Print Queue",
+    "Guard Gallivant",
+    "Bridge Repair",
+    "Resonant Collinearity",
+    "Disk Fragmenter",
+    "Hoof It",
+    "Plutonian Pebbles",
+    "Garden Groups",
+    "Claw Contraption",
+    "Restroom Redoubt",
+    "Warehouse Woes",
+    "Reindeer Maze",
+    "Chronospatial Computer",
+    "RAM Run",
+    "Linen Layout",
+    "Race Condition",
+    "Keypad Conundrum",
+    "Monkey Market",
+    "LAN Party",
+    "Crossed Wires",
+    "Code Chronicle",
+];
+
+/// Marks the start and end of the README sections replaced by the report.
+pub const BADGES_MARKERS: (&str, &str) = ("<!-- report:badges -->", "<!-- /report:badges -->");
+pub const TABLE_MARKERS: (&str, &str) = ("<!-- report:days -->", "<!-- /report:days -->");
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PartStatus {
+    Unimplemented,
+    /// The example answer is wrong, or the part returned an error.
+    Failed,
+    Passed,
+}
+
+#[derive(Debug, Clone)]
+pub struct PartReport {
+    pub status: PartStatus,
+    /// Whether Advent of Code accepted an answer to the part, see [`puzzle::verified_answers`].
+    pub verified: bool,
+    /// The best recorded time of the part, and whether it was measured with a release build.
+    pub best_time: Option<(Duration, bool)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub day: u8,
+    pub title: &'static str,
+    pub parts: [PartReport; 2],
+}
+
+impl PartReport {
+    /// A part earns a star once Advent of Code accepted its answer to the real input,
+    /// a passing example alone doesn't earn one.
+    pub fn has_star(&self) -> bool {
+        self.verified
+    }
+
+    fn star(&self) -> &'static str {
+        if self.has_star() { "⭐" } else { " " }
+    }
+}
+
+impl DayReport {
+    pub fn stars(&self) -> usize {
+        self.parts.iter().filter(|v| v.has_star()).count()
+    }
+}
+
+fn part_status(passed: bool, result: &Result<crate::days::Answer, SolveError>) -> PartStatus {
+    match (passed, result) {
+        (true, _) => PartStatus::Passed,
+        (false, Err(SolveError::Unimplemented)) => PartStatus::Unimplemented,
+        (false, _) => PartStatus::Failed,
+    }
+}
+
+/// The best time of a part among the recorded runs, preferring release builds.
+fn best_time(
+    records: &[RunRecord],
+    day: u8,
+    part: fn(&RunRecord) -> Option<u64>,
+) -> Option<(Duration, bool)> {
+    let best = |profile: &str| {
+        records
+            .iter()
+            .filter(|v| v.day == day && v.profile == profile)
+            .filter_map(part)
+            .min()
+            .map(Duration::from_nanos)
+    };
+
+    match best("release") {
+        Some(time) => Some((time, true)),
+        None => best("debug").map(|time| (time, false)),
+    }
+}
+
+/// Tests a day with its example.
+pub fn example_status(day: u8) -> [PartStatus; 2] {
+    let ((one_p, one_r, _), (two_p, two_r, _)) = match_and_test_day_both!();
+    [part_status(one_p, &one_r), part_status(two_p, &two_r)]
+}
+
+/// Tests every day with its example and collects the verified answers
+/// and the timings recorded in the history.
+pub fn collect(records: &[RunRecord]) -> Vec<DayReport> {
+    (1..=25)
+        .map(|day| {
+            let [one, two] = example_status(day);
+            let [one_verified, two_verified] = puzzle::verified_answers(day).map(|v| v.is_some());
+            DayReport {
+                day,
+                title: TITLES[day as usize - 1],
+                parts: [
+                    PartReport {
+                        status: one,
+                        verified: one_verified,
+                        best_time: best_time(records, day, |v| v.one_ns),
+                    },
+                    PartReport {
+                        status: two,
+                        verified: two_verified,
+                        best_time: best_time(records, day, |v| v.two_ns),
+                    },
+                ],
+            }
+        })
+        .collect()
+}
+
+pub fn status_symbol(status: PartStatus) -> &'static str {
+    match status {
+        PartStatus::Unimplemented => " ",
+        PartStatus::Failed => "✗",
+        PartStatus::Passed => "✓",
+    }
+}
+
+fn format_time(part: &PartReport) -> String {
+    match part.best_time {
+        Some((time, true)) => dynamic_range_time_format(&time),
+        Some((time, false)) => format!("{} (debug)", dynamic_range_time_format(&time)),
+        None => "-".to_string(),
+    }
+}
+
+/// Days that are fully solved, and days of which only one part is solved.
+fn completed(days: &[DayReport]) -> (usize, usize) {
+    (
+        days.iter().filter(|v| v.stars() == 2).count(),
+        days.iter().filter(|v| v.stars() == 1).count(),
+    )
+}
+
+/// The Markdown table of all days that have at least one part implemented.
+pub fn markdown_table(days: &[DayReport]) -> String {
+    let mut out =
+        String::from("| Day | Title | Example | Part 1 | Part 2 | Part 1 time | Part 2 time |\n");
+    out += "|----:|-------|:-------:|:------:|:------:|------------:|------------:|\n";
+
+    for day in days.iter().filter(|v| {
+        v.parts
+            .iter()
+            .any(|p| p.verified || p.status != PartStatus::Unimplemented)
+    }) {
+        out += &format!(
+            "| [{}](https://adventofcode.com/2024/day/{}) | {} | {} {} | {} | {} | {} | {} |\n",
+            day.day,
+            day.day,
+            day.title,
+            status_symbol(day.parts[0].status),
+            status_symbol(day.parts[1].status),
+            day.parts[0].star(),
+            day.parts[1].star(),
+            format_time(&day.parts[0]),
+            format_time(&day.parts[1]),
+        );
+    }
+
+    out
+}
+
+/// The "Days completed" and "Stars" badges.
+pub fn markdown_badges(days: &[DayReport]) -> String {
+    let (full, half) = completed(days);
+    let stars: usize = days.iter().map(|v| v.stars()).sum();
+
+    let completed = if half > 0 {
+        format!("{}%20%2B%20{}%20half", full, half)
+    } else {
+        full.to_string()
+    };
+    let color = if full == 25 { "brightgreen" } else { "red" };
+
+    format!(
+        "![Days completed](https://img.shields.io/badge/Days%20completed-{}-{}?style=flat-square)\n\
+         ![Stars](https://img.shields.io/badge/Stars-{}-yellow?style=flat-square)\n",
+        completed, color, stars
+    )
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// A standalone HTML page with the table of all days.
+pub fn html(days: &[DayReport]) -> String {
+    let (full, half) = completed(days);
+    let stars: usize = days.iter().map(|v| v.stars()).sum();
+
+    let mut rows = String::new();
+    for day in days {
+        rows += &format!(
+            "      <tr><td><a href=\"https://adventofcode.com/2024/day/{}\">{}</a></td><td>{}</td><td>{} {}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            day.day,
+            day.day,
+            html_escape(day.title),
+            status_symbol(day.parts[0].status),
+            status_symbol(day.parts[1].status),
+            day.parts[0].star(),
+            day.parts[1].star(),
+            html_escape(&format_time(&day.parts[0])),
+            html_escape(&format_time(&day.parts[1])),
+        );
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <title>Advent of Code 2024</title>
+    <style>
+      body {{ background: #0f0f23; color: #cccccc; font-family: monospace; }}
+      a {{ color: #009900; }}
+      table {{ border-collapse: collapse; }}
+      td, th {{ padding: 0.2em 1em; text-align: left; }}
+    </style>
+  </head>
+  <body>
+    <h1>Advent of Code 2024</h1>
+    <p>{} days completed, {} half, {} stars.</p>
+    <table>
+      <tr><th>Day</th><th>Title</th><th>Example</th><th>Part 1</th><th>Part 2</th><th>Part 1 time</th><th>Part 2 time</th></tr>
+{}    </table>
+  </body>
+</html>
+"#,
+        full, half, stars, rows
+    )
+}
+
+/// Replaces the text between the two markers, keeping the markers themselves.
+fn replace_section(
+    text: &str,
+    (begin, end): (&str, &str),
+    content: &str,
+) -> Result<String, String> {
+    let start = text
+        .find(begin)
+        .ok_or_else(|| format!("missing the marker {}", begin))?
+        + begin.len();
+    let stop = text[start..]
+        .find(end)
+        .ok_or_else(|| format!("missing the marker {}", end))?
+        + start;
+
+    Ok(format!("{}\n{}{}", &text[..start], content, &text[stop..]))
+}
+
+/// Replaces the marked badge and table sections of a README.
+///
+/// The badges count verified answers only, so they are kept as they are
+/// when no answers were verified here, for example on a fresh checkout.
+pub fn update_readme(readme: &str, days: &[DayReport]) -> Result<String, String> {
+    let verified = days.iter().any(|v| v.parts.iter().any(|p| p.verified));
+    let readme = match verified {
+        true => replace_section(readme, BADGES_MARKERS, &markdown_badges(days))?,
+        false => readme.to_string(),
+    };
+    replace_section(&readme, TABLE_MARKERS, &markdown_table(days))
+}
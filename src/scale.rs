@@ -0,0 +1,194 @@
+use crate::days::{SolveError, input_generator};
+use crate::fuzz::Rng;
+use crate::{Part, dynamic_range_time_format, extra_part_names, params, solve_day};
+use colored::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct ScaleSettings {
+    /// The size passed to the generator for the first step.
+    pub start: usize,
+    /// The factor by which the size grows with every step.
+    pub factor: f64,
+    pub steps: usize,
+    /// The number of runs per step, of which the fastest one counts.
+    pub repeats: usize,
+    pub seed: u64,
+    /// No larger inputs are tried after a step took longer than this.
+    pub max_time: Duration,
+}
+
+/// The timings of the fastest runs on inputs of one size, one per phase.
+struct Step {
+    size: usize,
+    bytes: usize,
+    times: Vec<Duration>,
+}
+
+/// Fits `time = c * bytes^k` by least squares on the logarithms, returning `k`.
+///
+/// Steps faster than a microsecond are mostly noise, so they are left out.
+/// Returns `None` if fewer than three steps remain.
+fn fit_exponent(steps: &[Step], phase: usize) -> Option<f64> {
+    let points: Vec<(f64, f64)> = steps
+        .iter()
+        .filter(|v| v.times[phase] >= Duration::from_micros(1))
+        .map(|v| ((v.bytes as f64).ln(), v.times[phase].as_secs_f64().ln()))
+        .collect();
+    if points.len() < 3 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|v| v.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|v| v.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|v| (v.0 - mean_x) * (v.1 - mean_y)).sum();
+    let variance: f64 = points.iter().map(|v| (v.0 - mean_x).powi(2)).sum();
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+fn complexity_name(exponent: f64) -> &'static str {
+    match exponent {
+        k if k < 0.5 => "constant",
+        k if k < 1.5 => "linear",
+        k if k < 2.5 => "quadratic",
+        k if k < 3.5 => "cubic",
+        _ => "worse than cubic",
+    }
+}
+
+/// Runs the given part of a day on generated inputs of growing size, printing the timings
+/// of every phase and the exponent of the curve fitted to them, measured against the length
+/// of the input.
+///
+/// Returns whether all inputs could be solved.
+pub fn scale(day: u8, part: Part, settings: &ScaleSettings) -> bool {
+    let Some(generator) = input_generator(day) else {
+        println!(
+            "{}",
+            format!("Day {} has no input generator.", day).red().bold()
+        );
+        return false;
+    };
+    if let Part::Extra(name) = &part
+        && !extra_part_names(day).contains(&name.as_str())
+    {
+        println!(
+            "{}",
+            SolveError::UnknownPart(name.clone())
+                .to_string()
+                .red()
+                .bold()
+        );
+        return false;
+    }
+    let _span = tracing::info_span!("day", day, mode = "scale").entered();
+
+    println!(
+        "{} Day {} over {} sizes, seed {}",
+        "Scaling".green().bold(),
+        day,
+        settings.steps,
+        settings.seed
+    );
+    println!("{}", "-----------------------".green().bold());
+
+    // The phases of a run that are timed separately, the parts depending on the one selected.
+    let mut phases = vec!["Parse".to_string(), "Precompute".to_string()];
+    let mut header = format!("{:>8} {:>10}", "Size", "Bytes");
+    let mut widths = vec![];
+
+    // Panics are reported below instead of being printed where they happen.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut steps: Vec<Step> = vec![];
+    let mut passed = true;
+    let mut size = settings.start as f64;
+    for _ in 0..settings.steps {
+        let step_size = size.round() as usize;
+        size *= settings.factor;
+        if steps.last().is_some_and(|v| v.size == step_size) {
+            continue;
+        }
+
+        let mut rng = Rng::new(settings.seed);
+        let input = (generator.generate)(&mut rng, step_size);
+
+        let mut times: Vec<Duration> = vec![];
+        for _ in 0..settings.repeats.max(1) {
+            let Ok(run) = panic::catch_unwind(AssertUnwindSafe(|| {
+                params::with_generated(|| solve_day(day, part.clone(), &input))
+            })) else {
+                println!(
+                    "{}",
+                    format!("The input of size {} panicked.", step_size)
+                        .red()
+                        .bold()
+                );
+                passed = false;
+                break;
+            };
+            let part_times = run.part_times();
+            if phases.len() == 2 {
+                phases.extend(part_times.iter().map(|v| v.0.clone()));
+                for phase in &phases {
+                    header += &format!(" {:>11}", phase);
+                    widths.push(phase.len().max(11));
+                }
+                println!("{}", header.bold());
+            }
+            let run_times = [run.init_t, run.pre_t]
+                .into_iter()
+                .chain(part_times.into_iter().map(|v| v.1));
+            if times.is_empty() {
+                times = run_times.collect();
+            } else {
+                for (time, run_time) in times.iter_mut().zip(run_times) {
+                    *time = (*time).min(run_time);
+                }
+            }
+        }
+        if !passed {
+            break;
+        }
+
+        let mut line = format!("{:>8} {:>10}", step_size, input.len());
+        for (time, width) in times.iter().zip(&widths) {
+            line += &format!(" {:>width$}", dynamic_range_time_format(time));
+        }
+        println!("{}", line);
+        let total: Duration = times.iter().sum();
+        steps.push(Step {
+            size: step_size,
+            bytes: input.len(),
+            times,
+        });
+        if total > settings.max_time {
+            println!(
+                "Stopping, as the last size took {}.",
+                dynamic_range_time_format(&total)
+            );
+            break;
+        }
+    }
+
+    panic::set_hook(default_hook);
+
+    println!("{}:", "Estimated complexity".green().bold());
+    let width = phases.iter().map(|v| v.len() + 1).fold(11, usize::max);
+    for (phase, name) in phases.iter().enumerate() {
+        match fit_exponent(&steps, phase) {
+            Some(exponent) => println!(
+                "\t{:width$} {} ({})",
+                format!("{}:", name),
+                format!("n^{:.2}", exponent).bold().blue(),
+                complexity_name(exponent)
+            ),
+            None => println!("\t{:width$} too fast to measure", format!("{}:", name)),
+        }
+    }
+
+    passed
+}
@@ -0,0 +1,232 @@
+use crate::days::{Answer, SolveError};
+use crate::report::{self, PartStatus, TITLES};
+use crate::{Part, input, solve_day};
+use colored::*;
+use serde::Serialize;
+use std::io::Read;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Debug, Clone)]
+pub struct ServeSettings {
+    pub port: u16,
+    /// The largest accepted input, in bytes.
+    pub max_body: usize,
+    /// How long a request may run before it is answered with an error.
+    /// The run itself can't be stopped and keeps going in the background,
+    /// other requests to solve are rejected until it ends.
+    pub timeout: Duration,
+}
+
+#[derive(Serialize)]
+struct DayInfo {
+    day: u8,
+    title: &'static str,
+    /// `passed`, `failed` or `unimplemented` per part, from testing the example.
+    parts: [&'static str; 2],
+}
+
+#[derive(Serialize)]
+struct PartResult {
+    part: u8,
+    /// The answer as it would be submitted, missing if the part failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    answer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    time_ns: u64,
+}
+
+#[derive(Serialize)]
+struct SolveResponse {
+    day: u8,
+    parse_ns: u64,
+    precompute_ns: u64,
+    parts: Vec<PartResult>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// The status code and JSON body of a response.
+type Reply = (u16, String);
+
+fn json(status: u16, body: &impl Serialize) -> Reply {
+    (
+        status,
+        serde_json::to_string(body).unwrap_or_else(|_| "{}".to_string()),
+    )
+}
+
+fn error(status: u16, message: impl Into<String>) -> Reply {
+    json(
+        status,
+        &ErrorResponse {
+            error: message.into(),
+        },
+    )
+}
+
+fn status_name(status: PartStatus) -> &'static str {
+    match status {
+        PartStatus::Unimplemented => "unimplemented",
+        PartStatus::Failed => "failed",
+        PartStatus::Passed => "passed",
+    }
+}
+
+fn part_result(part: u8, result: &Result<Answer, SolveError>, time: Duration) -> PartResult {
+    let (answer, error) = match result {
+        Ok(answer) => (Some(answer.as_text()), None),
+        Err(err) => (None, Some(err.to_string())),
+    };
+    PartResult {
+        part,
+        answer,
+        error,
+        time_ns: time.as_nanos() as u64,
+    }
+}
+
+/// Whether a day is being solved, including runs that timed out and were abandoned.
+static SOLVING: AtomicBool = AtomicBool::new(false);
+
+/// Solves a day on a separate thread, so panics can be caught and timeouts abandoned.
+///
+/// Only one run may be going at a time, so abandoned runs can't pile up.
+fn solve(day: u8, part: Part, input: String, timeout: Duration) -> Reply {
+    if SOLVING.swap(true, Ordering::AcqRel) {
+        return error(503, "A run that timed out is still going, try again later.");
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let result = panic::catch_unwind(|| {
+            let input = &*input::normalize(&input);
+            let run = solve_day(day, part, input);
+            let mut parts = vec![];
+            if run.runs_one() {
+                parts.push(part_result(1, &run.one, run.one_t));
+            }
+            if run.runs_two() {
+                parts.push(part_result(2, &run.two, run.two_t));
+            }
+            SolveResponse {
+                day,
+                parse_ns: run.init_t.as_nanos() as u64,
+                precompute_ns: run.pre_t.as_nanos() as u64,
+                parts,
+            }
+        });
+        SOLVING.store(false, Ordering::Release);
+        let _ = sender.send(result);
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(response)) => json(200, &response),
+        Ok(Err(_)) => error(500, format!("Day {} panicked on the input.", day)),
+        Err(_) => error(504, format!("Day {} took longer than {:?}.", day, timeout)),
+    }
+}
+
+/// Reads the body of a request, or `None` if it is larger than `max` bytes.
+fn read_body(request: &mut Request, max: usize) -> std::io::Result<Option<String>> {
+    if request.body_length().is_some_and(|v| v > max) {
+        return Ok(None);
+    }
+
+    let mut body = vec![];
+    request
+        .as_reader()
+        .take(max as u64 + 1)
+        .read_to_end(&mut body)?;
+    if body.len() > max {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+}
+
+fn handle(request: &mut Request, days: &[DayInfo], settings: &ServeSettings) -> Reply {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (request.method(), segments.as_slice()) {
+        (Method::Get, ["days"]) => json(
+            200,
+            &days
+                .iter()
+                .filter(|v| v.parts.iter().any(|v| *v != "unimplemented"))
+                .collect::<Vec<_>>(),
+        ),
+        (Method::Post, ["days", day]) => {
+            let Some(day) = day.parse::<u8>().ok().filter(|v| (1..=25).contains(v)) else {
+                return error(404, format!("There is no day {}.", day));
+            };
+            let part = match query
+                .split('&')
+                .find_map(|v| v.strip_prefix("part="))
+                .unwrap_or("both")
+            {
+                "1" => Part::One,
+                "2" => Part::Two,
+                "both" => Part::Both,
+                other => return error(400, format!("Unknown part {:?}, use 1, 2 or both.", other)),
+            };
+            match read_body(request, settings.max_body) {
+                Ok(Some(input)) => solve(day, part, input, settings.timeout),
+                Ok(None) => error(
+                    413,
+                    format!("The input is larger than {} bytes.", settings.max_body),
+                ),
+                Err(err) => error(400, format!("Couldn't read the input: {}", err)),
+            }
+        }
+        (_, ["days"] | ["days", _]) => error(405, "Use GET /days or POST /days/{n}."),
+        _ => error(404, "Not found."),
+    }
+}
+
+/// Serves the days over HTTP on localhost until the process is stopped.
+///
+/// Requests are handled one at a time, a panicking day is answered with an error.
+pub fn serve(settings: &ServeSettings) -> std::io::Result<()> {
+    let days: Vec<DayInfo> = (1..=25)
+        .map(|day| {
+            let status = panic::catch_unwind(|| report::example_status(day))
+                .unwrap_or([PartStatus::Failed; 2]);
+            DayInfo {
+                day,
+                title: TITLES[day as usize - 1],
+                parts: status.map(status_name),
+            }
+        })
+        .collect();
+
+    let server = Server::http(("127.0.0.1", settings.port)).map_err(std::io::Error::other)?;
+    println!(
+        "{} on http://{}",
+        "Serving".green().bold(),
+        server.server_addr()
+    );
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    for mut request in server.incoming_requests() {
+        let (status, body) = handle(&mut request, &days, settings);
+        println!("{} {} {}", request.method(), request.url(), status);
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(err) = request.respond(response) {
+            println!("{}", format!("Couldn't respond: {}", err).red());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use crossterm::{cursor, execute, terminal};
+
+/// Keeps the terminal in raw mode on the alternate screen, restoring it when dropped,
+/// so that errors and panics don't leave the terminal unusable.
+pub struct RawTerminal;
+
+impl RawTerminal {
+    pub fn enter() -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let guard = RawTerminal;
+        execute!(
+            std::io::stdout(),
+            terminal::EnterAlternateScreen,
+            cursor::Hide
+        )?;
+        Ok(guard)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(
+            std::io::stdout(),
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,16 @@
+use lazy_static::lazy_static;
+use mut_static::MutStatic;
+
+lazy_static! {
+    static ref SELECTED: MutStatic<Option<String>> = MutStatic::from(None);
+}
+
+/// Selects the variant used by all following runs and tests, `None` for the default one.
+pub fn select(name: Option<String>) {
+    *SELECTED.write().unwrap() = name;
+}
+
+/// The name of the selected variant.
+pub fn selected() -> Option<String> {
+    SELECTED.read().unwrap().clone()
+}
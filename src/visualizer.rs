@@ -0,0 +1,175 @@
+use lazy_static::lazy_static;
+use mut_static::MutStatic;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::sleep;
+use std::time::Duration;
+
+/// A single picture of a day's state.
+#[derive(Debug, Clone, Default)]
+pub struct Frame {
+    pub grid: Vec<Vec<char>>,
+    /// Cells drawn highlighted, as `(x, y)`.
+    pub highlights: Vec<(usize, usize)>,
+}
+
+impl Frame {
+    /// Renders the frame using ANSI escape codes, starting at the top left of the screen.
+    fn render(&self) -> String {
+        let mut out = String::from("\x1b[H\x1b[2J");
+
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if self.highlights.contains(&(x, y)) {
+                    out += &format!("\x1b[1;7m{}\x1b[0m", c);
+                } else {
+                    out.push(*c);
+                }
+            }
+            out += "\r\n";
+        }
+
+        out
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Output {
+    /// Draw the frames in the terminal.
+    Terminal,
+    /// Write the frames to an asciinema `.cast` file.
+    Cast(PathBuf),
+}
+
+enum Sink {
+    Terminal,
+    Cast {
+        path: PathBuf,
+        writer: Option<BufWriter<File>>,
+    },
+}
+
+pub struct Visualizer {
+    sink: Sink,
+    fps: u32,
+    frames: usize,
+    /// The first error writing a frame, after which no more frames are written.
+    error: Option<std::io::Error>,
+}
+
+lazy_static! {
+    static ref VISUALIZER: MutStatic<Option<Visualizer>> = MutStatic::from(None);
+}
+
+/// Mirrors whether [`VISUALIZER`] is set, so days don't lock it on every step.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Escapes a string for use in a JSON document.
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            '\r' => out += "\\r",
+            c if (c as u32) < 0x20 => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+impl Visualizer {
+    fn push(&mut self, frame: &Frame) -> std::io::Result<()> {
+        let time = self.frames as f64 / self.fps as f64;
+        self.frames += 1;
+
+        match &mut self.sink {
+            Sink::Terminal => {
+                print!("{}", frame.render());
+                std::io::stdout().flush()?;
+                sleep(Duration::from_secs_f64(1.0 / self.fps as f64));
+            }
+            Sink::Cast { path, writer } => {
+                if writer.is_none() {
+                    let mut w = BufWriter::new(File::create(path)?);
+                    writeln!(
+                        w,
+                        "{{\"version\": 2, \"width\": {}, \"height\": {}}}",
+                        frame.grid.iter().map(|v| v.len()).max().unwrap_or(0),
+                        frame.grid.len() + 1
+                    )?;
+                    *writer = Some(w);
+                }
+
+                writeln!(
+                    writer.as_mut().unwrap(),
+                    "[{:.6}, \"o\", \"{}\"]",
+                    time,
+                    json_escape(&frame.render())
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Enables the visualization of days that support it.
+pub fn enable(output: Output, fps: u32) {
+    let sink = match output {
+        Output::Terminal => Sink::Terminal,
+        Output::Cast(path) => Sink::Cast { path, writer: None },
+    };
+
+    *VISUALIZER.write().unwrap() = Some(Visualizer {
+        sink,
+        fps: fps.max(1),
+        frames: 0,
+        error: None,
+    });
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Passes a frame to the visualizer, if it is enabled.
+///
+/// Prefer the [`visualize!`](crate::visualize) macro, which doesn't build the frame when disabled.
+/// An error writing the frame is logged and returned by [`finish`], and stops the visualization.
+pub fn push_frame(frame: Frame) {
+    if let Some(visualizer) = VISUALIZER.write().unwrap().as_mut()
+        && visualizer.error.is_none()
+        && let Err(err) = visualizer.push(&frame)
+    {
+        tracing::error!("Error while writing visualization frame: {}", err);
+        visualizer.error = Some(err);
+    }
+}
+
+/// Finishes the visualization, writing all outstanding frames.
+///
+/// Returns the number of frames shown, or the first error writing them.
+pub fn finish() -> std::io::Result<usize> {
+    match VISUALIZER.write().unwrap().as_mut() {
+        Some(visualizer) => {
+            if let Some(err) = visualizer.error.take() {
+                return Err(err);
+            }
+            if let Sink::Cast {
+                writer: Some(writer),
+                ..
+            } = &mut visualizer.sink
+            {
+                writer.flush()?;
+            }
+            Ok(visualizer.frames)
+        }
+        None => Ok(0),
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    Answers, ImageExport, Part, format_solution, input, run_day_answers, test_day_answers,
+};
+use colored::*;
+use crossterm::{cursor, execute, terminal};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::fs;
+use std::io::stdout;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How long to wait for more changes after one, as editors often write a file in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The example of a day in the source tree, which `test --watch` reads instead of the embedded one.
+fn example_path(day: u8) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("src/days/test_inputs/test{:02}.txt", day))
+}
+
+/// Prints how the answers changed since the previous run.
+fn print_diff(previous: &Option<Answers>, answers: &Answers) {
+    let Some(previous) = previous else {
+        return;
+    };
+
+    println!("{}:", "Changes".green().bold());
+    for (name, answer) in answers {
+        let before = previous.iter().find(|v| v.0 == *name).map(|v| &v.1);
+        match before {
+            Some(before) if before == answer => println!("\t{}: unchanged", name.green()),
+            Some(before) => println!(
+                "\t{}: {} → {}",
+                name.green(),
+                format_solution(before),
+                format_solution(answer)
+            ),
+            None => println!("\t{}: {}", name.green(), format_solution(answer)),
+        }
+    }
+}
+
+/// Blocks until the file changes.
+fn wait_for_change(
+    receiver: &mpsc::Receiver<notify::Result<notify::Event>>,
+    file: &Path,
+) -> notify::Result<()> {
+    let is_change = |event: notify::Result<notify::Event>| -> notify::Result<bool> {
+        let event = event?;
+        Ok(
+            matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                && event.paths.iter().any(|path| path == file),
+        )
+    };
+
+    loop {
+        let event = receiver
+            .recv()
+            .map_err(|err| notify::Error::generic(&err.to_string()))?;
+        if is_change(event)? {
+            break;
+        }
+    }
+    while receiver.recv_timeout(DEBOUNCE).is_ok() {}
+    Ok(())
+}
+
+/// Reruns `run` every time the file changes, clearing the screen before every run
+/// and printing how the answers changed since the previous one.
+///
+/// Only returns if watching the file fails.
+fn watch(file: &Path, mut run: impl FnMut() -> Answers) -> notify::Result<()> {
+    let file = fs::canonicalize(file).unwrap_or_else(|_| file.to_owned());
+
+    // The directory is watched instead of the file itself,
+    // as many editors save by replacing the file, which would end a watch on it.
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    if let Some(dir) = file.parent() {
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    }
+
+    let mut previous: Option<Answers> = None;
+    loop {
+        let _ = execute!(
+            stdout(),
+            terminal::Clear(terminal::ClearType::All),
+            terminal::Clear(terminal::ClearType::Purge),
+            cursor::MoveTo(0, 0)
+        );
+        println!("{} {}", "Watching".green().bold(), file.display());
+        println!();
+
+        // A panicking parser shouldn't end the watch, only this run.
+        match panic::catch_unwind(AssertUnwindSafe(&mut run)) {
+            Ok(answers) => {
+                print_diff(&previous, &answers);
+                previous = Some(answers);
+            }
+            Err(_) => println!("{}", "The day panicked.".red().bold()),
+        }
+
+        wait_for_change(&receiver, &file)?;
+    }
+}
+
+/// Runs a day on an input file every time it changes.
+pub fn watch_run(
+    day: u8,
+    part: Part,
+    path: &Path,
+    export: Option<&ImageExport>,
+) -> notify::Result<()> {
+    watch(path, || match fs::read_to_string(path) {
+        Ok(input) => run_day_answers(day, part.clone(), &input, export).1,
+        Err(err) => {
+            println!(
+                "{}",
+                format!("Error while reading {}: {}", path.display(), err)
+                    .red()
+                    .bold()
+            );
+            vec![]
+        }
+    })
+}
+
+/// Tests a day every time its example in the source tree changes.
+pub fn watch_test(day: u8, part: Part, export: Option<&ImageExport>) -> notify::Result<()> {
+    let path = example_path(day);
+    watch(&path, || {
+        match fs::read_to_string(&path) {
+            Ok(example) => input::override_example(Some(&example)),
+            Err(err) => {
+                println!(
+                    "{}",
+                    format!(
+                        "Error while reading {}, testing the embedded example: {}",
+                        path.display(),
+                        err
+                    )
+                    .yellow()
+                    .bold()
+                );
+                input::override_example(None);
+            }
+        }
+        test_day_answers(day, part.clone(), export).1
+    })
+}
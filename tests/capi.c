@@ -0,0 +1,37 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "aoc24.h"
+
+static const char *EXAMPLE = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+static int failures = 0;
+
+static void expect(uint8_t day, uint8_t part, const char *input, size_t len, Aoc24Kind kind,
+                   const char *answer) {
+    Aoc24Result *result = aoc24_solve(day, part, (const uint8_t *)input, len);
+    if (result->kind != kind || (answer != NULL && strcmp(result->answer, answer) != 0)) {
+        fprintf(stderr, "day %d part %d: expected %d \"%s\", got %d \"%s\"\n", day, part, kind,
+                answer ? answer : "", result->kind, result->answer);
+        failures++;
+    } else {
+        printf("day %d part %d: %d \"%s\" (parse %llu ns, part %llu ns)\n", day, part,
+               result->kind, result->answer, (unsigned long long)result->parse_ns,
+               (unsigned long long)result->part_ns);
+    }
+    aoc24_free_result(result);
+}
+
+int main(void) {
+    size_t len = strlen(EXAMPLE);
+    expect(1, 1, EXAMPLE, len, AOC24_KIND_OK, "11");
+    expect(1, 2, EXAMPLE, len, AOC24_KIND_OK, "31");
+    expect(25, 1, "", 0, AOC24_KIND_UNIMPLEMENTED, NULL);
+    expect(25, 1, NULL, 0, AOC24_KIND_UNIMPLEMENTED, NULL);
+    expect(0, 1, EXAMPLE, len, AOC24_KIND_INVALID_ARGUMENTS, NULL);
+    expect(1, 3, EXAMPLE, len, AOC24_KIND_INVALID_ARGUMENTS, NULL);
+    expect(1, 1, "\xff\xfe", 2, AOC24_KIND_INVALID_ARGUMENTS, NULL);
+    expect(1, 1, "1 x", 3, AOC24_KIND_PANICKED, NULL);
+    aoc24_free_result(NULL);
+    return failures != 0;
+}
@@ -0,0 +1,134 @@
+//! Builds the C ABI as a shared library and calls it from `tests/capi.c`,
+//! and checks that `include/aoc24.h`, as generated by cbindgen, matches the Rust declarations.
+#![cfg(feature = "capi")]
+
+use aoc24::capi::{Aoc24Kind, Aoc24Result, aoc24_free_result, aoc24_solve};
+use std::env;
+use std::fs;
+use std::mem::{offset_of, size_of};
+use std::path::Path;
+use std::process::Command;
+
+/// The signatures the header declares, see [`header_matches`].
+const _: unsafe extern "C" fn(u8, u8, *const u8, usize) -> *mut Aoc24Result = aoc24_solve;
+const _: unsafe extern "C" fn(*mut Aoc24Result) = aoc24_free_result;
+
+/// Every kind with the name of its constant in the header.
+const KINDS: [Aoc24Kind; 7] = [
+    Aoc24Kind::Ok,
+    Aoc24Kind::NoSolution,
+    Aoc24Kind::InvalidInput,
+    Aoc24Kind::Unimplemented,
+    Aoc24Kind::UnknownVariant,
+    Aoc24Kind::Panicked,
+    Aoc24Kind::InvalidArguments,
+];
+
+fn constant_name(kind: Aoc24Kind) -> &'static str {
+    // Fails to compile when a kind is added, so it can't be missing from KINDS and the header.
+    match kind {
+        Aoc24Kind::Ok => "AOC24_KIND_OK",
+        Aoc24Kind::NoSolution => "AOC24_KIND_NO_SOLUTION",
+        Aoc24Kind::InvalidInput => "AOC24_KIND_INVALID_INPUT",
+        Aoc24Kind::Unimplemented => "AOC24_KIND_UNIMPLEMENTED",
+        Aoc24Kind::UnknownVariant => "AOC24_KIND_UNKNOWN_VARIANT",
+        Aoc24Kind::Panicked => "AOC24_KIND_PANICKED",
+        Aoc24Kind::InvalidArguments => "AOC24_KIND_INVALID_ARGUMENTS",
+    }
+}
+
+fn cc() -> Command {
+    Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+}
+
+/// Compiles static assertions that the header's types, constants and functions
+/// match the ones in `src/capi.rs`.
+#[test]
+fn header_matches() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut checks = String::from("#include \"aoc24.h\"\n\n");
+    checks += &format!(
+        "_Static_assert(sizeof(Aoc24Kind) == {}, \"size of Aoc24Kind\");\n",
+        size_of::<Aoc24Kind>()
+    );
+    for kind in KINDS {
+        checks += &format!(
+            "_Static_assert({0} == {1}, \"value of {0}\");\n",
+            constant_name(kind),
+            kind as i32
+        );
+    }
+    checks += &format!(
+        "_Static_assert(sizeof(Aoc24Result) == {}, \"size of Aoc24Result\");\n",
+        size_of::<Aoc24Result>()
+    );
+    for (field, offset) in [
+        ("kind", offset_of!(Aoc24Result, kind)),
+        ("answer", offset_of!(Aoc24Result, answer)),
+        ("parse_ns", offset_of!(Aoc24Result, parse_ns)),
+        ("precompute_ns", offset_of!(Aoc24Result, precompute_ns)),
+        ("part_ns", offset_of!(Aoc24Result, part_ns)),
+    ] {
+        checks += &format!(
+            "_Static_assert(offsetof(Aoc24Result, {0}) == {1}, \"offset of {0}\");\n",
+            field, offset
+        );
+    }
+    checks +=
+        "\nAoc24Result *(*const solve)(uint8_t, uint8_t, const uint8_t *, size_t) = aoc24_solve;\n";
+    checks += "void (*const free_result)(Aoc24Result *) = aoc24_free_result;\n";
+
+    let target = root.join("target").join("capi");
+    fs::create_dir_all(&target).unwrap();
+    let file = target.join("header_checks.c");
+    fs::write(&file, checks).unwrap();
+
+    let status = cc()
+        .arg(&file)
+        .arg("-I")
+        .arg(root.join("include"))
+        .args(["-fsyntax-only", "-Werror"])
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(
+        status.success(),
+        "include/aoc24.h doesn't match src/capi.rs"
+    );
+}
+
+#[test]
+fn c_program() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // A separate target directory, as the one running the tests is locked by cargo.
+    let target = root.join("target").join("capi");
+    let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .current_dir(root)
+        .args(["build", "--lib", "--features", "capi"])
+        .arg("--target-dir")
+        .arg(&target)
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success(), "building the library failed");
+
+    let lib = target.join("debug");
+    let program = target.join("capi_test");
+    let status = cc()
+        .arg(root.join("tests").join("capi.c"))
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg("-L")
+        .arg(&lib)
+        .arg(format!("-Wl,-rpath,{}", lib.display()))
+        .args(["-laoc24", "-o"])
+        .arg(&program)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "compiling tests/capi.c failed");
+
+    // cargo points the library path at its own target directory, which has a libaoc24 too.
+    let status = Command::new(&program)
+        .env("LD_LIBRARY_PATH", &lib)
+        .status()
+        .expect("failed to run the C program");
+    assert!(status.success(), "the C program reported failures");
+}